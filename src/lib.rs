@@ -4,6 +4,7 @@
 extern crate hyper;
 extern crate xml;
 extern crate encoding;
+extern crate miniz_oxide;
 extern crate codegen;
 
 #[macro_use]
@@ -13,14 +14,86 @@ mod wsdl;
 pub mod autogen;
 
 pub use wsdl::schema::{
+    AddressProtocol,
+    HttpBinding,
+    HttpOperation,
     Documented,
     NamedItem,
     Wsdl,
+    WsdlAddress,
     WsdlBinding,
     WsdlOperationBinding,
     WsdlInputBinding,
     WsdlOutputBinding,
     WsdlFaultBinding,
+    WsdlExtension,
+    WsdlImport,
+    WsdlMessage,
+    WsdlMessagePart,
+    WsdlOperation,
+    WsdlOperationMessage,
     WsdlPort,
-    WsdlService
+    WsdlPortType,
+    WsdlService,
+    WsdlTypes,
+    WsdlVersion
+};
+pub use wsdl::bundle::{Bundle, ExternalReference};
+pub use wsdl::cache::{CacheMode, CachingResolver};
+pub use wsdl::catalog::{Catalog, CatalogResolver};
+pub use wsdl::diagnostics::{render_error, Diagnostic, Severity};
+pub use wsdl::element::{XmlElement, XmlNode};
+pub use wsdl::errors::{Error, ErrorKind, SourcePosition};
+pub use wsdl::graph::{
+    LinkedMessage,
+    LinkedOperation,
+    LinkedPart,
+    LinkedPort,
+    LinkedService,
+    ServiceGraph
+};
+pub use wsdl::resolver::{
+    DefaultResolver,
+    Fetched,
+    FileResolver,
+    HttpResolver,
+    MemoryResolver,
+    Resolver,
+    Validators
+};
+pub use wsdl::schema_set::{SchemaSet, XsdEffectiveType};
+pub use wsdl::soap::{
+    SoapBinding,
+    SoapBody,
+    SoapFault,
+    SoapHeader,
+    SoapHeaderFault,
+    SoapOperation,
+    SoapStyle,
+    SoapUse,
+    SoapVersion
+};
+pub use wsdl::xsd::{
+    XsdAny,
+    XsdAttribute,
+    XsdAttributeGroup,
+    XsdAttributeUse,
+    XsdComplexType,
+    XsdCompositor,
+    XsdContent,
+    XsdDerivation,
+    XsdDerivationMethod,
+    XsdElement,
+    XsdFacet,
+    XsdForm,
+    XsdGroup,
+    XsdGroupRef,
+    XsdImport,
+    XsdModelGroup,
+    XsdParticle,
+    XsdSchema,
+    XsdSimpleType,
+    XsdSimpleVariety,
+    XsdTypeRef,
+    XsdWhiteSpace
 };
@@ -0,0 +1,345 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+use xml::reader::{EventReader, XmlEvent};
+
+use super::errors::*;
+use super::file;
+use super::import::{resolve_location, Importer};
+use super::resolver::{is_url, Resolver};
+use super::schema::{decode_contents, find_attribute, Wsdl};
+
+/// WSDL and XSD documents shipped together, read from a zip archive or a
+/// directory. Documents are addressed by their path from the root of the
+/// bundle starting with `/`, imports are resolved against those paths.
+#[derive(Debug, Default, Clone)]
+pub struct Bundle {
+    documents: BTreeMap<String, Vec<u8>>,
+}
+
+/// Import or include of a bundled document that is not part of the bundle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalReference {
+    pub document: String,
+    pub location: String,
+}
+
+impl Bundle {
+    pub fn from_zip(location: &str) -> Result<Bundle> {
+        Bundle::from_zip_bytes(&file::load(location)?)
+    }
+
+    pub fn from_zip_bytes(bytes: &[u8]) -> Result<Bundle> {
+        let mut documents = BTreeMap::new();
+        for (name, contents) in read_zip(bytes)? {
+            documents.insert(bundle_path(&name), contents);
+        }
+
+        Ok(Bundle { documents })
+    }
+
+    pub fn from_directory(location: &str) -> Result<Bundle> {
+        let mut bundle = Bundle::default();
+        bundle.read_directory(Path::new(location), "")?;
+        Ok(bundle)
+    }
+
+    fn read_directory(&mut self, directory: &Path, prefix: &str) -> Result<()> {
+        for entry in fs::read_dir(directory)? {
+            let entry = entry?;
+            let name = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+            if entry.file_type()?.is_dir() {
+                self.read_directory(&entry.path(), &name)?;
+            } else {
+                self.documents.insert(name, file::load(&entry.path().to_string_lossy())?);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Paths of the bundled documents.
+    pub fn documents(&self) -> Vec<&str> {
+        self.documents.keys().map(|k| k.as_str()).collect()
+    }
+
+    /// Loads the definitions of the entry point, given by its path in the
+    /// bundle or, when that is unambiguous, by its file name.
+    pub fn load(&self, entry: &str) -> Result<Wsdl> {
+        Importer::new(self).load(&self.locate(entry)?)
+    }
+
+    fn locate(&self, entry: &str) -> Result<String> {
+        let path = bundle_path(entry);
+        if self.documents.contains_key(&path) {
+            return Ok(path);
+        }
+
+        let mut found = self.documents.keys().filter(|k| k.ends_with(&path));
+        match (found.next(), found.next()) {
+            (Some(path), None) => Ok(path.clone()),
+            _ => Err(ErrorKind::InvalidLocation(entry.to_string()).into()),
+        }
+    }
+
+    /// Imports and includes of every bundled document, WSDL and XML Schema
+    /// alike, that point outside the bundle.
+    pub fn external_references(&self) -> Result<Vec<ExternalReference>> {
+        let mut references = Vec::new();
+
+        for (document, contents) in &self.documents {
+            // Documents that are not XML, such as bundled manuals or images,
+            // hold no references.
+            let contents = match decode_contents(contents) {
+                Ok(contents) => contents,
+                Err(_) => continue,
+            };
+            for event in EventReader::new(&contents[..]) {
+                let (name, attributes) = match event {
+                    Ok(XmlEvent::StartElement { name, attributes, .. }) => (name, attributes),
+                    Ok(_) => continue,
+                    Err(_) => break,
+                };
+                if !["import", "include", "redefine"].contains(&name.local_name.as_str()) {
+                    continue;
+                }
+
+                let location = find_attribute("location", &attributes)
+                    .or_else(|| find_attribute("schemaLocation", &attributes));
+                if let Some(location) = location {
+                    if !self.contains(&resolve_location(document, &location)?) {
+                        references.push(ExternalReference {
+                                            document: document.clone(),
+                                            location,
+                                        });
+                    }
+                }
+            }
+        }
+
+        Ok(references)
+    }
+
+    fn contains(&self, location: &str) -> bool {
+        !is_url(location) &&
+        self.normalize(location)
+            .map(|key| self.documents.contains_key(&key))
+            .unwrap_or(false)
+    }
+}
+
+impl Resolver for Bundle {
+    fn fetch(&self, location: &str) -> Result<Vec<u8>> {
+        self.documents
+            .get(&self.normalize(location)?)
+            .cloned()
+            .ok_or_else(|| ErrorKind::InvalidLocation(location.to_string()).into())
+    }
+}
+
+fn bundle_path(name: &str) -> String {
+    format!("/{}", name.trim_start_matches('/'))
+}
+
+/// Files of a zip archive, stored and deflated entries are supported.
+/// Largest uncompressed size of a zipped document, and of all of them.
+const MAX_ENTRY_SIZE: usize = 64 << 20;
+const MAX_BUNDLE_SIZE: usize = 256 << 20;
+
+/// Size of a central directory entry without its name, extra field and comment.
+const ENTRY_HEADER_SIZE: usize = 46;
+
+/// Reads the files of a zip archive. Zip64 archives are not supported, and
+/// archives whose documents would inflate beyond `MAX_ENTRY_SIZE` or
+/// `MAX_BUNDLE_SIZE` are rejected before they are decompressed.
+fn read_zip(bytes: &[u8]) -> Result<Vec<(String, Vec<u8>)>> {
+    let invalid = |reason: &str| -> Error { ErrorKind::InvalidArchive(reason.to_string()).into() };
+
+    // The end of central directory record is followed by a comment of up to 64k.
+    let end = (0..bytes.len().saturating_sub(21))
+        .rev()
+        .take(0x10000 + 22)
+        .find(|&i| read_u32(bytes, i) == Some(0x0605_4b50))
+        .ok_or_else(|| invalid("end of central directory not found"))?;
+    let count = read_u16(bytes, end + 10).ok_or_else(|| invalid("truncated archive"))?;
+    let mut offset = read_u32(bytes, end + 16).ok_or_else(|| invalid("truncated archive"))? as usize;
+    if count == 0xFFFF || offset == 0xFFFF_FFFF {
+        return Err(invalid("zip64 archives are not supported"));
+    }
+    if offset > end || (end - offset) / ENTRY_HEADER_SIZE < count as usize {
+        return Err(invalid("central directory does not fit the archive"));
+    }
+
+    let mut files = Vec::new();
+    let mut total_size = 0;
+    for _ in 0..count {
+        if read_u32(bytes, offset) != Some(0x0201_4b50) {
+            return Err(invalid("corrupt central directory"));
+        }
+        let field = |at: usize| read_u16(bytes, offset + at).map(|v| v as usize);
+        let (method, name_length, extra_length, comment_length) =
+            match (field(10), field(28), field(30), field(32)) {
+                (Some(m), Some(n), Some(e), Some(c)) => (m, n, e, c),
+                _ => return Err(invalid("truncated archive")),
+            };
+        let (compressed_size, size) = match (read_u32(bytes, offset + 20),
+                                             read_u32(bytes, offset + 24)) {
+            (Some(c), Some(s)) => (c, s),
+            _ => return Err(invalid("truncated archive")),
+        };
+        if compressed_size == 0xFFFF_FFFF || size == 0xFFFF_FFFF {
+            return Err(invalid("zip64 archives are not supported"));
+        }
+        let (compressed_size, size) = (compressed_size as usize, size as usize);
+        let header = read_u32(bytes, offset + 42)
+            .ok_or_else(|| invalid("truncated archive"))? as usize;
+        let name = bytes
+            .get(offset + ENTRY_HEADER_SIZE..offset + ENTRY_HEADER_SIZE + name_length)
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .ok_or_else(|| invalid("truncated archive"))?;
+        offset += ENTRY_HEADER_SIZE + name_length + extra_length + comment_length;
+
+        if name.ends_with('/') {
+            continue;
+        }
+
+        total_size += size;
+        if size > MAX_ENTRY_SIZE || total_size > MAX_BUNDLE_SIZE {
+            return Err(invalid(&format!("`{}` is too large", name)));
+        }
+
+        let data = match (read_u16(bytes, header + 26), read_u16(bytes, header + 28)) {
+            (Some(n), Some(e)) => header + 30 + n as usize + e as usize,
+            _ => return Err(invalid("truncated archive")),
+        };
+        let raw = bytes
+            .get(data..data + compressed_size)
+            .ok_or_else(|| invalid("truncated archive"))?;
+
+        let contents = match method {
+            0 => raw.to_vec(),
+            8 => {
+                decompress_to_vec_with_limit(raw, size)
+                    .map_err(|_| invalid(&format!("corrupt entry `{}`", name)))?
+            }
+            _ => return Err(invalid(&format!("unsupported compression of `{}`", name))),
+        };
+        if contents.len() != size {
+            return Err(invalid(&format!("size of `{}` does not match", name)));
+        }
+        files.push((name, contents));
+    }
+
+    Ok(files)
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    bytes.get(at..at + 2).map(|b| u16::from(b[0]) | u16::from(b[1]) << 8)
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    match (read_u16(bytes, at), read_u16(bytes, at + 2)) {
+        (Some(low), Some(high)) => Some(u32::from(low) | u32::from(high) << 16),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::file::example;
+
+    #[test]
+    fn loads_zip_bundle_test() {
+        let bundle = Bundle::from_zip(&example("bundle.zip")).unwrap();
+
+        assert_eq!(vec!["/bundle/wsdl/Abstract.wsdl",
+                        "/bundle/wsdl/Service.wsdl",
+                        "/bundle/xsd/Quote.xsd"],
+                   bundle.documents());
+
+        let wsdl = bundle.load("Service.wsdl").unwrap();
+        assert_eq!("QuoteService", wsdl.services[0].name);
+        assert_eq!("QuotePortType", wsdl.port_types[0].name);
+    }
+
+    #[test]
+    fn reports_external_references_test() {
+        let mut bundle = Bundle::from_directory(&example("bundle")).unwrap();
+        bundle
+            .documents
+            .insert("/doc/Manual.pdf".to_string(), b"%PDF-1.4\n\xe2\x28\xa1\xff".to_vec());
+
+        assert!(bundle.load("wsdl/Service.wsdl").is_ok());
+        assert_eq!(vec![ExternalReference {
+                            document: "/xsd/Quote.xsd".to_string(),
+                            location: "../../common/Common.xsd".to_string(),
+                        }],
+                   bundle.external_references().unwrap());
+    }
+
+    /// Archive of a single stored file whose central directory declares
+    /// `size` as its uncompressed size.
+    fn stored_zip(name: &str, contents: &[u8], size: u32) -> Vec<u8> {
+        fn u16le(out: &mut Vec<u8>, value: u16) {
+            out.extend_from_slice(&[value as u8, (value >> 8) as u8]);
+        }
+        fn u32le(out: &mut Vec<u8>, value: u32) {
+            u16le(out, value as u16);
+            u16le(out, (value >> 16) as u16);
+        }
+
+        let mut zip = Vec::new();
+        u32le(&mut zip, 0x0403_4b50);
+        zip.extend_from_slice(&[0; 22]);
+        u16le(&mut zip, name.len() as u16);
+        u16le(&mut zip, 0);
+        zip.extend_from_slice(name.as_bytes());
+        zip.extend_from_slice(contents);
+
+        let directory = zip.len() as u32;
+        u32le(&mut zip, 0x0201_4b50);
+        zip.extend_from_slice(&[0; 16]);
+        u32le(&mut zip, contents.len() as u32);
+        u32le(&mut zip, size);
+        u16le(&mut zip, name.len() as u16);
+        zip.extend_from_slice(&[0; 12]);
+        u32le(&mut zip, 0);
+        zip.extend_from_slice(name.as_bytes());
+
+        let directory_size = zip.len() as u32 - directory;
+        u32le(&mut zip, 0x0605_4b50);
+        zip.extend_from_slice(&[0; 6]);
+        u16le(&mut zip, 1);
+        u32le(&mut zip, directory_size);
+        u32le(&mut zip, directory);
+        u16le(&mut zip, 0);
+        zip
+    }
+
+    #[test]
+    fn rejects_oversized_and_inconsistent_entries_test() {
+        let zip = stored_zip("a.xsd", b"<a/>", 4);
+        assert_eq!(vec!["/a.xsd"], Bundle::from_zip_bytes(&zip).unwrap().documents());
+
+        let errors = [stored_zip("a.xsd", b"<a/>", 1 << 30),
+                      stored_zip("a.xsd", b"<a/>", 0xFFFF_FFFF),
+                      stored_zip("a.xsd", b"<a/>", 5)]
+                .iter()
+                .map(|zip| Bundle::from_zip_bytes(zip).unwrap_err().to_string())
+                .collect::<Vec<_>>();
+        assert_eq!(vec!["Invalid zip archive: `a.xsd` is too large",
+                        "Invalid zip archive: zip64 archives are not supported",
+                        "Invalid zip archive: size of `a.xsd` does not match"],
+                   errors);
+
+        let mut zip = stored_zip("a.xsd", b"<a/>", 4);
+        let count = zip.len() - 12;
+        zip[count] = 0xFE;
+        zip[count + 1] = 0xFF;
+        assert_eq!("Invalid zip archive: central directory does not fit the archive",
+                   Bundle::from_zip_bytes(&zip).unwrap_err().to_string());
+    }
+}
@@ -0,0 +1,368 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+
+use super::errors::*;
+use super::resolver::{is_url, Fetched, Resolver, Validators};
+
+/// How `CachingResolver` keeps the documents it fetched.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CacheMode {
+    /// Documents are fetched on every load.
+    None,
+    /// Documents are kept for the lifetime of the resolver.
+    Memory,
+    /// Remote documents are stored in `directory` and fetched again once
+    /// they are older than `ttl`.
+    Disk { directory: PathBuf, ttl: Duration },
+    /// Remote documents are stored in `directory` and revalidated on every
+    /// load with their `ETag` and `Last-Modified` validators.
+    Revalidate { directory: PathBuf },
+}
+
+/// Caches the documents of another resolver. With the disk modes a stored
+/// copy is served, however old, when the origin cannot be reached.
+pub struct CachingResolver<R> {
+    inner: R,
+    mode: CacheMode,
+    memory: RefCell<HashMap<String, Vec<u8>>>,
+}
+
+struct CachedDocument {
+    contents: Vec<u8>,
+    validators: Validators,
+    age: Duration,
+}
+
+impl<R: Resolver> CachingResolver<R> {
+    pub fn new(inner: R, mode: CacheMode) -> CachingResolver<R> {
+        CachingResolver {
+            inner,
+            mode,
+            memory: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn fetch_memory(&self, key: String, location: &str) -> Result<Vec<u8>> {
+        if let Some(contents) = self.memory.borrow().get(&key) {
+            return Ok(contents.clone());
+        }
+
+        let contents = self.inner.fetch(location)?;
+        self.memory.borrow_mut().insert(key, contents.clone());
+        Ok(contents)
+    }
+
+    fn fetch_disk(&self, path: &Path, location: &str, ttl: Duration) -> Result<Vec<u8>> {
+        let cached = read_entry(path);
+        if let Some(ref cached) = cached {
+            if cached.age < ttl {
+                return Ok(cached.contents.clone());
+            }
+        }
+
+        match self.inner.fetch(location) {
+            Ok(contents) => {
+                // A cache that cannot be written must not fail the fetch.
+                let _ = write_entry(path, &contents, &Validators::default());
+                Ok(contents)
+            }
+            Err(error) => cached.map(|c| c.contents).ok_or(error),
+        }
+    }
+
+    fn fetch_revalidated(&self, path: &Path, location: &str) -> Result<Vec<u8>> {
+        let cached = read_entry(path);
+        let validators = cached
+            .as_ref()
+            .map(|c| c.validators.clone())
+            .unwrap_or_default();
+
+        match self.inner.fetch_if_modified(location, &validators) {
+            Ok(Fetched::Modified(contents, validators)) => {
+                let _ = write_entry(path, &contents, &validators);
+                Ok(contents)
+            }
+            Ok(Fetched::NotModified) => {
+                match cached {
+                    Some(cached) => Ok(cached.contents),
+                    None => self.inner.fetch(location),
+                }
+            }
+            Err(error) => cached.map(|c| c.contents).ok_or(error),
+        }
+    }
+}
+
+impl<R: Resolver> Resolver for CachingResolver<R> {
+    fn fetch(&self, location: &str) -> Result<Vec<u8>> {
+        let key = self.inner.normalize(location)?;
+
+        match self.mode {
+            CacheMode::None => self.inner.fetch(location),
+            CacheMode::Memory => self.fetch_memory(key, location),
+            CacheMode::Disk { .. } |
+            CacheMode::Revalidate { .. } if !is_url(&key) => self.inner.fetch(location),
+            CacheMode::Disk { ref directory, ttl } => {
+                self.fetch_disk(&entry_path(directory, &key), location, ttl)
+            }
+            CacheMode::Revalidate { ref directory } => {
+                self.fetch_revalidated(&entry_path(directory, &key), location)
+            }
+        }
+    }
+
+    fn normalize(&self, location: &str) -> Result<String> {
+        self.inner.normalize(location)
+    }
+
+    fn locate_namespace(&self, namespace: &str) -> Option<String> {
+        self.inner.locate_namespace(namespace)
+    }
+}
+
+/// Cache entries are named by a hash of the document location, the body is
+/// stored next to a file holding its validators.
+fn entry_path(directory: &Path, key: &str) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    directory.join(format!("{:016x}", hasher.finish()))
+}
+
+fn read_entry(path: &Path) -> Option<CachedDocument> {
+    let mut contents = Vec::new();
+    File::open(path.with_extension("xml"))
+        .and_then(|mut file| file.read_to_end(&mut contents))
+        .ok()?;
+
+    let age = fs::metadata(path.with_extension("xml"))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .unwrap_or_default();
+
+    let mut validators = Validators::default();
+    let mut meta = String::new();
+    if File::open(path.with_extension("meta"))
+           .and_then(|mut file| file.read_to_string(&mut meta))
+           .is_ok() {
+        for line in meta.lines() {
+            let mut header = line.splitn(2, ": ");
+            match (header.next(), header.next()) {
+                (Some("ETag"), Some(value)) => validators.etag = Some(value.to_string()),
+                (Some("Last-Modified"), Some(value)) => {
+                    validators.last_modified = Some(value.to_string())
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    Some(CachedDocument {
+             contents,
+             validators,
+             age,
+         })
+}
+
+fn write_entry(path: &Path, contents: &[u8], validators: &Validators) -> Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+
+    let mut meta = String::new();
+    if let Some(ref etag) = validators.etag {
+        meta.push_str(&format!("ETag: {}\n", etag));
+    }
+    if let Some(ref last_modified) = validators.last_modified {
+        meta.push_str(&format!("Last-Modified: {}\n", last_modified));
+    }
+
+    // The body goes first: validators next to an older body would let a
+    // revalidation keep serving it.
+    replace_file(&path.with_extension("xml"), contents)?;
+    replace_file(&path.with_extension("meta"), meta.as_bytes())
+}
+
+/// Writes a temporary file and renames it over `path`, so that readers see
+/// either the old contents or the new ones in full.
+fn replace_file(path: &Path, contents: &[u8]) -> Result<()> {
+    static WRITES: AtomicUsize = AtomicUsize::new(0);
+
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}-{}.tmp", process::id(), WRITES.fetch_add(1, Ordering::SeqCst)));
+    let temporary = PathBuf::from(name);
+
+    let written = File::create(&temporary)
+        .and_then(|mut file| file.write_all(contents).and_then(|_| file.sync_all()))
+        .and_then(|_| fs::rename(&temporary, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    Ok(written?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::file::TempDir;
+    use std::cell::Cell;
+
+    /// Serves a single document and counts the requests made for it.
+    struct Origin {
+        contents: RefCell<Option<&'static str>>,
+        etag: &'static str,
+        requests: Cell<usize>,
+    }
+
+    impl Origin {
+        fn new(contents: &'static str) -> Origin {
+            Origin {
+                contents: RefCell::new(Some(contents)),
+                etag: "\"v1\"",
+                requests: Cell::new(0),
+            }
+        }
+    }
+
+    impl Resolver for &Origin {
+        fn fetch(&self, location: &str) -> Result<Vec<u8>> {
+            self.requests.set(self.requests.get() + 1);
+            match *self.contents.borrow() {
+                Some(contents) => Ok(contents.as_bytes().to_vec()),
+                None => {
+                    Err(ErrorKind::HttpStatus(location.to_string(),
+                                              "503 Service Unavailable".to_string())
+                                .into())
+                }
+            }
+        }
+
+        fn fetch_if_modified(&self, location: &str, validators: &Validators) -> Result<Fetched> {
+            let available = self.contents.borrow().is_some();
+            if available && validators.etag.as_deref() == Some(self.etag) {
+                self.requests.set(self.requests.get() + 1);
+                return Ok(Fetched::NotModified);
+            }
+
+            let contents = self.fetch(location)?;
+            Ok(Fetched::Modified(contents,
+                                 Validators {
+                                     etag: Some(self.etag.to_string()),
+                                     last_modified: None,
+                                 }))
+        }
+    }
+
+    const LOCATION: &str = "http://example.com/service.wsdl";
+
+    #[test]
+    fn memory_mode_fetches_once_test() {
+        let origin = Origin::new("<definitions/>");
+        let resolver = CachingResolver::new(&origin, CacheMode::Memory);
+
+        resolver.fetch(LOCATION).unwrap();
+        assert_eq!(b"<definitions/>".to_vec(), resolver.fetch(LOCATION).unwrap());
+        assert_eq!(1, origin.requests.get());
+    }
+
+    #[test]
+    fn disk_mode_reuses_fresh_copy_and_serves_stale_one_test() {
+        let dir = TempDir::new("cache");
+        let directory = dir.join("disk");
+        let origin = Origin::new("<definitions/>");
+
+        let fresh = CacheMode::Disk {
+            directory: directory.clone(),
+            ttl: Duration::from_secs(3600),
+        };
+        CachingResolver::new(&origin, fresh.clone()).fetch(LOCATION).unwrap();
+        CachingResolver::new(&origin, fresh).fetch(LOCATION).unwrap();
+        assert_eq!(1, origin.requests.get());
+
+        *origin.contents.borrow_mut() = None;
+        let expired = CacheMode::Disk {
+            directory,
+            ttl: Duration::from_secs(0),
+        };
+        let contents = CachingResolver::new(&origin, expired).fetch(LOCATION).unwrap();
+        assert_eq!(b"<definitions/>".to_vec(), contents);
+        assert_eq!(2, origin.requests.get());
+    }
+
+    #[test]
+    fn revalidate_mode_sends_validators_test() {
+        let dir = TempDir::new("cache");
+        let mode = CacheMode::Revalidate { directory: dir.join("revalidate") };
+        let origin = Origin::new("<definitions/>");
+
+        CachingResolver::new(&origin, mode.clone()).fetch(LOCATION).unwrap();
+        *origin.contents.borrow_mut() = Some("<changed/>");
+        let contents = CachingResolver::new(&origin, mode).fetch(LOCATION).unwrap();
+
+        assert_eq!(b"<definitions/>".to_vec(), contents);
+        assert_eq!(2, origin.requests.get());
+    }
+
+    #[test]
+    fn revalidate_mode_serves_stale_copy_when_origin_fails_test() {
+        let dir = TempDir::new("cache");
+        let mode = CacheMode::Revalidate { directory: dir.join("revalidate") };
+        let origin = Origin::new("<definitions/>");
+
+        CachingResolver::new(&origin, mode.clone()).fetch(LOCATION).unwrap();
+        *origin.contents.borrow_mut() = None;
+        let contents = CachingResolver::new(&origin, mode).fetch(LOCATION).unwrap();
+
+        assert_eq!(b"<definitions/>".to_vec(), contents);
+        assert_eq!(2, origin.requests.get());
+    }
+
+    #[test]
+    fn serves_documents_when_cache_cannot_be_written_test() {
+        let dir = TempDir::new("cache");
+        let directory = dir.join("blocked");
+        File::create(&directory).unwrap();
+        let origin = Origin::new("<definitions/>");
+
+        let disk = CacheMode::Disk {
+            directory: directory.clone(),
+            ttl: Duration::from_secs(3600),
+        };
+        let contents = CachingResolver::new(&origin, disk).fetch(LOCATION).unwrap();
+        assert_eq!(b"<definitions/>".to_vec(), contents);
+
+        let revalidate = CacheMode::Revalidate { directory };
+        let contents = CachingResolver::new(&origin, revalidate).fetch(LOCATION).unwrap();
+        assert_eq!(b"<definitions/>".to_vec(), contents);
+        assert_eq!(2, origin.requests.get());
+    }
+
+    #[test]
+    fn write_entry_replaces_body_and_validators_test() {
+        let dir = TempDir::new("cache");
+        let directory = dir.join("entry");
+        let path = directory.join("entry");
+        let validators = |etag: &str| {
+            Validators {
+                etag: Some(etag.to_string()),
+                last_modified: None,
+            }
+        };
+
+        write_entry(&path, b"<old/>", &validators("\"v1\"")).unwrap();
+        write_entry(&path, b"<new/>", &validators("\"v2\"")).unwrap();
+
+        let cached = read_entry(&path).unwrap();
+        assert_eq!(b"<new/>".to_vec(), cached.contents);
+        assert_eq!(Some("\"v2\"".to_string()), cached.validators.etag);
+        assert_eq!(2, fs::read_dir(&directory).unwrap().count());
+    }
+}
@@ -0,0 +1,270 @@
+use xml::attribute::OwnedAttribute;
+use xml::reader::{EventReader, XmlEvent};
+
+use super::errors::*;
+use super::file;
+use super::import::resolve_location;
+use super::resolver::{Fetched, Resolver, Validators};
+use super::schema::find_attribute;
+
+pub const NS_CATALOG: &str = "urn:oasis:names:tc:entity:xmlns:xml:catalog";
+
+/// OASIS XML catalog mapping namespaces and document locations onto other,
+/// usually local, locations. `uri` and `system` entries match a whole
+/// location, `rewriteURI` and `rewriteSystem` its start and `uriSuffix` and
+/// `systemSuffix` its end. Exact matches win over rewrites and rewrites over
+/// suffixes, the longest match wins among entries of a kind.
+#[derive(Debug, Default, Clone)]
+pub struct Catalog {
+    entries: Vec<CatalogEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum CatalogEntry {
+    Exact { name: String, uri: String },
+    Rewrite { start: String, prefix: String },
+    Suffix { suffix: String, uri: String },
+}
+
+impl Catalog {
+    pub fn new() -> Catalog {
+        Catalog { entries: Vec::new() }
+    }
+
+    pub fn load_from_file(location: &str) -> Result<Catalog> {
+        Catalog::parse(&file::load(location)?, location)
+    }
+
+    /// Parses a catalog document, relative locations in its entries are
+    /// resolved against `base`, the location of the catalog.
+    pub fn parse(contents: &[u8], base: &str) -> Result<Catalog> {
+        let mut catalog = Catalog::new();
+
+        for event in EventReader::new(contents) {
+            if let XmlEvent::StartElement {
+                       ref name,
+                       ref attributes,
+                       ..
+                   } = event? {
+                if name.namespace.as_deref() != Some(NS_CATALOG) {
+                    continue;
+                }
+
+                let entry = match name.local_name.as_str() {
+                    "uri" | "system" => {
+                        let id = if name.local_name == "uri" { "name" } else { "systemId" };
+                        CatalogEntry::Exact {
+                            name: entry_attribute(id, &name.local_name, attributes)?,
+                            uri: resolve_location(base,
+                                                  &entry_attribute("uri",
+                                                                   &name.local_name,
+                                                                   attributes)?)?,
+                        }
+                    }
+                    "rewriteURI" | "rewriteSystem" => {
+                        let id = if name.local_name == "rewriteURI" {
+                            "uriStartString"
+                        } else {
+                            "systemIdStartString"
+                        };
+                        CatalogEntry::Rewrite {
+                            start: entry_attribute(id, &name.local_name, attributes)?,
+                            prefix: resolve_location(base,
+                                                     &entry_attribute("rewritePrefix",
+                                                                      &name.local_name,
+                                                                      attributes)?)?,
+                        }
+                    }
+                    "uriSuffix" | "systemSuffix" => {
+                        let id = if name.local_name == "uriSuffix" {
+                            "uriSuffix"
+                        } else {
+                            "systemIdSuffix"
+                        };
+                        CatalogEntry::Suffix {
+                            suffix: entry_attribute(id, &name.local_name, attributes)?,
+                            uri: resolve_location(base,
+                                                  &entry_attribute("uri",
+                                                                   &name.local_name,
+                                                                   attributes)?)?,
+                        }
+                    }
+                    _ => continue,
+                };
+                catalog.entries.push(entry);
+            }
+        }
+
+        Ok(catalog)
+    }
+
+    /// Maps a namespace to the location of its schema.
+    pub fn add_uri(&mut self, name: &str, uri: &str) {
+        self.entries.push(CatalogEntry::Exact {
+                              name: name.to_string(),
+                              uri: uri.to_string(),
+                          });
+    }
+
+    pub fn add_rewrite(&mut self, start: &str, prefix: &str) {
+        self.entries.push(CatalogEntry::Rewrite {
+                              start: start.to_string(),
+                              prefix: prefix.to_string(),
+                          });
+    }
+
+    pub fn add_suffix(&mut self, suffix: &str, uri: &str) {
+        self.entries.push(CatalogEntry::Suffix {
+                              suffix: suffix.to_string(),
+                              uri: uri.to_string(),
+                          });
+    }
+
+    /// Location a namespace or document location is mapped to, if any.
+    pub fn resolve(&self, identifier: &str) -> Option<String> {
+        let exact = self.entries
+            .iter()
+            .filter_map(|entry| match *entry {
+                            CatalogEntry::Exact { ref name, ref uri } if name == identifier => {
+                                Some(uri.clone())
+                            }
+                            _ => None,
+                        })
+            .next();
+
+        let rewrite = || {
+            self.entries
+                .iter()
+                .filter_map(|entry| match *entry {
+                                CatalogEntry::Rewrite { ref start, ref prefix }
+                                    if identifier.starts_with(start.as_str()) => {
+                                    Some((start.len(),
+                                          format!("{}{}", prefix, &identifier[start.len()..])))
+                                }
+                                _ => None,
+                            })
+                .max_by_key(|&(length, _)| length)
+                .map(|(_, uri)| uri)
+        };
+
+        let suffix = || {
+            self.entries
+                .iter()
+                .filter_map(|entry| match *entry {
+                                CatalogEntry::Suffix { ref suffix, ref uri }
+                                    if identifier.ends_with(suffix.as_str()) => {
+                                    Some((suffix.len(), uri.clone()))
+                                }
+                                _ => None,
+                            })
+                .max_by_key(|&(length, _)| length)
+                .map(|(_, uri)| uri)
+        };
+
+        exact.or_else(rewrite).or_else(suffix)
+    }
+}
+
+/// Loads documents from the locations a catalog maps them to, locations
+/// without an entry are passed on as they are.
+pub struct CatalogResolver<R> {
+    catalog: Catalog,
+    inner: R,
+}
+
+impl<R: Resolver> CatalogResolver<R> {
+    pub fn new(catalog: Catalog, inner: R) -> CatalogResolver<R> {
+        CatalogResolver { catalog, inner }
+    }
+
+    fn map(&self, location: &str) -> String {
+        self.catalog
+            .resolve(location)
+            .unwrap_or_else(|| location.to_string())
+    }
+}
+
+impl<R: Resolver> Resolver for CatalogResolver<R> {
+    fn fetch(&self, location: &str) -> Result<Vec<u8>> {
+        self.inner.fetch(&self.map(location))
+    }
+
+    fn fetch_if_modified(&self, location: &str, validators: &Validators) -> Result<Fetched> {
+        self.inner.fetch_if_modified(&self.map(location), validators)
+    }
+
+    fn normalize(&self, location: &str) -> Result<String> {
+        self.inner.normalize(&self.map(location))
+    }
+
+    fn locate_namespace(&self, namespace: &str) -> Option<String> {
+        self.catalog
+            .resolve(namespace)
+            .or_else(|| self.inner.locate_namespace(namespace))
+    }
+}
+
+fn entry_attribute(name: &str, element: &str, attributes: &[OwnedAttribute]) -> Result<String> {
+    find_attribute(name, attributes)
+        .ok_or_else(|| {
+                        ErrorKind::MandatoryAttribute(name.to_string(), format!("catalog:{}", element))
+                            .into()
+                    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::file::TempDir;
+    use super::super::resolver::DefaultResolver;
+    use super::super::schema::Wsdl;
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    const CATALOG: &str = r#"<?xml version="1.0"?>
+<catalog xmlns="urn:oasis:names:tc:entity:xmlns:xml:catalog">
+    <uri name="urn:common" uri="common/Common.xsd"/>
+    <group>
+        <rewriteURI uriStartString="http://example.com/" rewritePrefix="mirror/"/>
+        <rewriteURI uriStartString="http://example.com/schemas/" rewritePrefix="schemas/"/>
+    </group>
+    <systemSuffix systemIdSuffix="Session.xsd" uri="session/Session.xsd"/>
+</catalog>
+"#;
+
+    #[test]
+    fn resolves_entries_test() {
+        let catalog = Catalog::parse(CATALOG.as_bytes(), "/opt/catalog/catalog.xml").unwrap();
+
+        assert_eq!(Some("/opt/catalog/common/Common.xsd".to_string()),
+                   catalog.resolve("urn:common"));
+        assert_eq!(Some("/opt/catalog/schemas/air/Air.xsd".to_string()),
+                   catalog.resolve("http://example.com/schemas/air/Air.xsd"));
+        assert_eq!(Some("/opt/catalog/mirror/Air.wsdl".to_string()),
+                   catalog.resolve("http://example.com/Air.wsdl"));
+        assert_eq!(Some("/opt/catalog/session/Session.xsd".to_string()),
+                   catalog.resolve("../../Session.xsd"));
+        assert_eq!(None, catalog.resolve("urn:other"));
+    }
+
+    #[test]
+    fn loads_missing_imports_from_catalog_test() {
+        let dir = TempDir::new("catalog");
+        File::create(dir.join("SessionContext_v1.xsd"))
+            .unwrap()
+            .write_all(br#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"/>"#)
+            .unwrap();
+
+        let mut catalog = Catalog::new();
+        catalog.add_suffix("SessionContext_v1/SessionContext_v1.xsd",
+                           dir.join("SessionContext_v1.xsd").to_str().unwrap());
+        let resolver = CatalogResolver::new(catalog, DefaultResolver);
+
+        let mut location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        location.push("examples/travel/Air.wsdl");
+        let wsdl = Wsdl::load_with_resolver(location.to_str().unwrap(), &resolver).unwrap();
+
+        assert!(!wsdl.port_types.is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+use super::errors::*;
+
+use encoding::all::{UTF_16BE, UTF_16LE, UTF_8};
+use encoding::label::encoding_from_whatwg_label;
+use encoding::{DecoderTrap, EncodingRef};
+
+/// Decodes a document using the encoding given by its byte order mark or,
+/// without one, by its XML declaration. Documents declaring neither are UTF-8.
+pub fn decode(bytes: &[u8]) -> Result<String> {
+    let (encoding, bom_length) = detect(bytes)?;
+
+    encoding
+        .decode(&bytes[bom_length..], DecoderTrap::Strict)
+        .map_err(|_| ErrorKind::InvalidEncoding(encoding.name().to_string()).into())
+}
+
+/// Encoding of the document and the length of its byte order mark.
+fn detect(bytes: &[u8]) -> Result<(EncodingRef, usize)> {
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        return Ok((UTF_8, 3));
+    }
+    if bytes.starts_with(&[0xFE, 0xFF]) {
+        return Ok((UTF_16BE, 2));
+    }
+    if bytes.starts_with(&[0xFF, 0xFE]) {
+        return Ok((UTF_16LE, 2));
+    }
+
+    // UTF-16 without a byte order mark is recognized by the `<?` of the declaration.
+    if bytes.starts_with(&[0x00, b'<', 0x00, b'?']) {
+        return Ok((UTF_16BE, 0));
+    }
+    if bytes.starts_with(&[b'<', 0x00, b'?', 0x00]) {
+        return Ok((UTF_16LE, 0));
+    }
+
+    match declared_encoding(bytes) {
+        Some(label) => {
+            encoding_from_whatwg_label(&label)
+                .map(|encoding| (encoding, 0))
+                .ok_or_else(|| ErrorKind::UnknownEncoding(label).into())
+        }
+        None => Ok((UTF_8, 0)),
+    }
+}
+
+/// Value of the `encoding` pseudo-attribute of the XML declaration. The
+/// declaration is ASCII in every encoding this is used for.
+fn declared_encoding(bytes: &[u8]) -> Option<String> {
+    if !bytes.starts_with(b"<?xml") {
+        return None;
+    }
+
+    let end = bytes.iter().position(|&b| b == b'>')?;
+    let declaration = String::from_utf8_lossy(&bytes[..end]);
+    let start = declaration.find("encoding")? + "encoding".len();
+    let rest = declaration[start..].trim_start();
+    if !rest.starts_with('=') {
+        return None;
+    }
+
+    let rest = rest[1..].trim_start();
+    let quote = rest.chars().next().filter(|&c| c == '"' || c == '\'')?;
+    let value = &rest[1..];
+
+    value.find(quote).map(|end| value[..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_declared_encoding_test() {
+        let bytes = b"<?xml version='1.0' encoding = 'ISO-8859-1'?><a>\xE9t\xE9</a>";
+
+        assert_eq!("<?xml version='1.0' encoding = 'ISO-8859-1'?><a>été</a>",
+                   decode(bytes).unwrap());
+    }
+
+    #[test]
+    fn decodes_utf16_with_bom_test() {
+        let mut bytes = vec![0xFF, 0xFE];
+        for unit in "<?xml version=\"1.0\" encoding=\"UTF-16\"?><a>ü</a>".encode_utf16() {
+            bytes.push(unit as u8);
+            bytes.push((unit >> 8) as u8);
+        }
+
+        assert_eq!("<?xml version=\"1.0\" encoding=\"UTF-16\"?><a>ü</a>",
+                   decode(&bytes).unwrap());
+    }
+
+    #[test]
+    fn rejects_unknown_and_malformed_encoding_test() {
+        match decode(b"<?xml version=\"1.0\" encoding=\"x-unknown\"?><a/>") {
+            Err(Error(ErrorKind::UnknownEncoding(ref label), _)) => assert_eq!("x-unknown", label),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+        match decode(b"<a>\xFF</a>") {
+            Err(Error(ErrorKind::InvalidEncoding(ref name), _)) => assert_eq!("utf-8", name),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use super::errors::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Problem found while reading a document in lenient mode or while resolving
+/// the components read from it.
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub kind: ErrorKind,
+    /// `None` for problems of XML Schema components, which keep no position.
+    pub position: Option<SourcePosition>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity,
+               kind: ErrorKind,
+               position: Option<SourcePosition>)
+               -> Diagnostic {
+        Diagnostic {
+            severity,
+            kind,
+            position,
+        }
+    }
+
+    /// Renders the diagnostic like `render_error` does.
+    pub fn render(&self, source: &[u8]) -> String {
+        let level = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+
+        render(level, &self.kind.to_string(), self.position.as_ref(), source)
+    }
+}
+
+/// Renders an error raised while parsing `source` together with the offending
+/// line and a caret under the column it was raised at:
+///
+/// ```text
+/// error: Attribute `name` is mandatory for `wsdl:message` element
+///  --> service.wsdl:3:5
+///   |
+/// 3 |     <wsdl:message>
+///   |     ^
+/// ```
+pub fn render_error(error: &Error, source: &[u8]) -> String {
+    render("error", &error.to_string(), error.position(), source)
+}
+
+fn render(level: &str, message: &str, position: Option<&SourcePosition>, source: &[u8]) -> String {
+    let mut rendered = format!("{}: {}\n", level, message);
+
+    let position = match position {
+        Some(position) => position,
+        None => return rendered,
+    };
+
+    let source = String::from_utf8_lossy(source);
+    let line = match source.lines().nth((position.line as usize).saturating_sub(1)) {
+        Some(line) => line,
+        None => {
+            rendered.push_str(&format!(" --> {}\n", position));
+            return rendered;
+        }
+    };
+
+    // Tabs are kept so that the caret lines up with the source line.
+    let padding = line.chars()
+        .take((position.column as usize).saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+    let number = position.line.to_string();
+    let gutter = " ".repeat(number.len());
+
+    rendered.push_str(&format!("{} --> {}\n", gutter, position));
+    rendered.push_str(&format!("{} |\n", gutter));
+    rendered.push_str(&format!("{} | {}\n", number, line));
+    rendered.push_str(&format!("{} | {}^\n", gutter, padding));
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wsdl::schema::Wsdl;
+
+    const SOURCE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/">
+    <wsdl:message>
+        <wsdl:part name="body" element="body"/>
+    </wsdl:message>
+</wsdl:definitions>
+"#;
+
+    #[test]
+    fn records_error_position_test() {
+        let error = Wsdl::parse(SOURCE.as_bytes()).unwrap_err();
+
+        match *error.kind() {
+            ErrorKind::MandatoryAttribute(ref attribute, _) => assert_eq!("name", attribute),
+            ref other => panic!("Unexpected error: {:?}", other),
+        }
+        assert_eq!(Some(&SourcePosition::new(3, 5)), error.position());
+    }
+
+    #[test]
+    fn renders_source_snippet_test() {
+        let error = Wsdl::parse(SOURCE.as_bytes()).unwrap_err();
+
+        assert_eq!("error: Attribute `name` is mandatory for `wsdl:message` element
+  --> 3:5
+  |
+3 |     <wsdl:message>
+  |     ^
+",
+                   render_error(&error, SOURCE.as_bytes()));
+    }
+
+    #[test]
+    fn renders_diagnostic_without_position_test() {
+        let diagnostic = Diagnostic::new(Severity::Warning,
+                                         ErrorKind::InvalidElement("wsdl:unknown".to_string()),
+                                         None);
+
+        assert_eq!("warning: Invalid `wsdl:unknown` element\n",
+                   diagnostic.render(SOURCE.as_bytes()));
+    }
+
+    #[test]
+    fn records_file_of_errors_and_diagnostics_test() {
+        let error = Wsdl::parse_in_file(SOURCE.as_bytes(), "service.wsdl").unwrap_err();
+        assert_eq!("service.wsdl:3:5", error.position().unwrap().to_string());
+
+        let (_, diagnostics) = Wsdl::parse_lenient_in_file(SOURCE.as_bytes(), "service.wsdl")
+            .unwrap();
+        assert_eq!(Some("service.wsdl".to_string()),
+                   diagnostics[0].position.as_ref().unwrap().file);
+    }
+
+    #[test]
+    fn records_position_of_chained_errors_test() {
+        let cause = ::std::io::Error::new(::std::io::ErrorKind::NotFound, "missing");
+        let error = Error::with_chain(cause, ErrorKind::InvalidLocation("a.wsdl".to_string()))
+            .at(SourcePosition::new(2, 3));
+
+        assert_eq!(Some(&SourcePosition::new(2, 3)), error.position());
+        let chain = error.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(vec!["Invalid document location `a.wsdl`", "2:3", "missing"], chain);
+    }
+}
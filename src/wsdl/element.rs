@@ -0,0 +1,151 @@
+use super::errors::*;
+use super::reader::Reader;
+
+use std::collections::BTreeMap;
+
+use xml::attribute::OwnedAttribute;
+use xml::name::OwnedName;
+use xml::namespace::{Namespace, NS_XMLNS_PREFIX, NS_XML_PREFIX};
+use xml::reader::XmlEvent;
+
+/// Owned XML element, used for content the model does not interpret.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XmlElement {
+    pub name: OwnedName,
+    pub attributes: Vec<OwnedAttribute>,
+    /// Namespace declarations made on this element. The root of a tree
+    /// declares every namespace in scope, so it can be written on its own.
+    pub namespaces: BTreeMap<String, String>,
+    pub children: Vec<XmlNode>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+}
+
+impl XmlElement {
+    /// Reads the root element of a decoded document.
+    pub fn parse(decoded_contents: &[u8]) -> Result<XmlElement> {
+        XmlElement::parse_with(Reader::new(decoded_contents))
+    }
+
+    /// Reads the root element of a decoded document read from `location`,
+    /// which is the file of the positions of its errors.
+    pub fn parse_in_file(decoded_contents: &[u8], location: &str) -> Result<XmlElement> {
+        XmlElement::parse_with(Reader::new(decoded_contents).in_file(location))
+    }
+
+    fn parse_with(mut iter: Reader) -> Result<XmlElement> {
+        XmlElement::read_root(&mut iter).map_err(|e| iter.locate(e))
+    }
+
+    fn read_root(iter: &mut Reader) -> Result<XmlElement> {
+        while let Some(v) = iter.next() {
+            if let XmlEvent::StartElement {
+                       ref name,
+                       ref attributes,
+                       ref namespace,
+                   } = v? {
+                return XmlElement::read(name, attributes, namespace, iter);
+            }
+        }
+
+        Err(ErrorKind::MissingElement("root".to_string()).into())
+    }
+
+    /// Reads the element whose start tag was just read, up to its end tag.
+    /// Whitespace between elements is dropped.
+    pub(crate) fn read(name: &OwnedName,
+                       attributes: &[OwnedAttribute],
+                       namespace: &Namespace,
+                       iter: &mut Reader)
+                       -> Result<XmlElement> {
+        XmlElement::read_nested(name, attributes, namespace, None, iter)
+    }
+
+    fn read_nested(name: &OwnedName,
+                   attributes: &[OwnedAttribute],
+                   namespace: &Namespace,
+                   parent: Option<&Namespace>,
+                   iter: &mut Reader)
+                   -> Result<XmlElement> {
+        let mut children = Vec::new();
+
+        while let Some(v) = iter.next() {
+            match v? {
+                XmlEvent::StartElement {
+                    name: ref child_name,
+                    attributes: ref child_attributes,
+                    namespace: ref child_namespace,
+                } => {
+                    children.push(XmlNode::Element(XmlElement::read_nested(child_name,
+                                                                           child_attributes,
+                                                                           child_namespace,
+                                                                           Some(namespace),
+                                                                           iter)?))
+                }
+                XmlEvent::EndElement { .. } => break,
+                XmlEvent::Characters(value) |
+                XmlEvent::CData(value) => children.push(XmlNode::Text(value)),
+                _ => continue,
+            }
+        }
+
+        Ok(XmlElement {
+               name: name.clone(),
+               attributes: attributes.to_vec(),
+               namespaces: declared_namespaces(namespace, parent),
+               children,
+           })
+    }
+
+    /// Child elements, text is skipped.
+    pub fn elements(&self) -> Vec<&XmlElement> {
+        self.children
+            .iter()
+            .filter_map(|c| match *c {
+                            XmlNode::Element(ref element) => Some(element),
+                            XmlNode::Text(_) => None,
+                        })
+            .collect()
+    }
+
+    /// Concatenated text content of the element and its descendants.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for child in &self.children {
+            match *child {
+                XmlNode::Element(ref element) => text.push_str(&element.text()),
+                XmlNode::Text(ref value) => text.push_str(value),
+            }
+        }
+        text
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|a| a.name.namespace.is_none() && a.name.local_name == name)
+            .map(|a| a.value.as_str())
+    }
+}
+
+/// Namespaces in scope of an element that are not in scope of its parent,
+/// or every namespace in scope without a parent. Predefined prefixes and the
+/// empty default namespace are left out.
+pub fn declared_namespaces(namespace: &Namespace,
+                           parent: Option<&Namespace>)
+                           -> BTreeMap<String, String> {
+    namespace
+        .0
+        .iter()
+        .filter(|&(prefix, uri)| {
+                    prefix != NS_XML_PREFIX && prefix != NS_XMLNS_PREFIX &&
+                    !(prefix.is_empty() && uri.is_empty())
+                })
+        .filter(|&(prefix, uri)| parent.and_then(|p| p.get(prefix)) != Some(uri.as_str()))
+        .map(|(prefix, uri)| (prefix.clone(), uri.clone()))
+        .collect()
+}
@@ -1,5 +1,7 @@
 use std::io::Error as IoError;
 use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt;
 
 use hyper::Error as HyperError;
 use xml::reader::Error as XmlError;
@@ -17,6 +19,11 @@ error_chain! {
                 display("Attribute `{}` is mandatory for `{}` element", attribute, element)
         }
 
+        InvalidAttributeValue(attribute: String, element: String, value: String) {
+            description("invalid attribute value")
+                display("Invalid value `{}` of attribute `{}` for `{}` element", value, attribute, element)
+        }
+
         InvalidElement(element: String) {
             description("invalid element")
                 display("Invalid `{}` element", element)
@@ -26,6 +33,86 @@ error_chain! {
             description("missing element")
                 display("Required `{}` element is missing from WSDL document", element)
         }
+
+        InvalidQName(value: String) {
+            description("invalid qualified name")
+                display("Invalid qualified name `{}`", value)
+        }
+
+        UndeclaredPrefix(prefix: String, value: String) {
+            description("undeclared namespace prefix")
+                display("Namespace prefix `{}` of `{}` is not declared", prefix, value)
+        }
+
+        MissingImport(location: String, importer: String) {
+            description("missing import")
+                display("Document `{}` imported from `{}` could not be loaded", location, importer)
+        }
+
+        ImportCycle(chain: Vec<String>) {
+            description("import cycle")
+                display("Import cycle detected: {}", chain.join(" -> "))
+        }
+
+        InvalidLocation(location: String) {
+            description("invalid location")
+                display("Invalid document location `{}`", location)
+        }
+
+        UnresolvedReference(kind: String, name: String, referrer: String) {
+            description("unresolved reference")
+                display("{} `{}` referenced from `{}` is not defined", kind, name, referrer)
+        }
+
+        AmbiguousReference(kind: String, name: String, referrer: String) {
+            description("ambiguous reference")
+                display("{} `{}` referenced from `{}` is defined more than once", kind, name, referrer)
+        }
+
+        UnknownEncoding(label: String) {
+            description("unknown encoding")
+                display("Document encoding `{}` is not supported", label)
+        }
+
+        InvalidEncoding(encoding: String) {
+            description("invalid encoding")
+                display("Document is not valid `{}`", encoding)
+        }
+
+        ForeignComponent(kind: String, name: String, namespace: String) {
+            description("foreign component")
+                display("{} `{}` of namespace `{}` cannot be inlined into another target namespace", kind, name, namespace)
+        }
+
+        CircularReference(kind: String, chain: Vec<String>) {
+            description("circular reference")
+                display("{} references form a cycle: {}", kind, chain.join(" -> "))
+        }
+
+        InvalidPattern(pattern: String) {
+            description("invalid pattern")
+                display("Invalid or unsupported pattern `{}`", pattern)
+        }
+
+        InvalidValue(value: String, reason: String) {
+            description("invalid value")
+                display("Invalid value `{}`: {}", value, reason)
+        }
+
+        UnsupportedType(name: String) {
+            description("unsupported type")
+                display("Values of built-in type `xs:{}` cannot be validated", name)
+        }
+
+        InvalidArchive(reason: String) {
+            description("invalid archive")
+                display("Invalid zip archive: {}", reason)
+        }
+
+        HttpStatus(url: String, status: String) {
+            description("unsuccessful HTTP status")
+                display("Request for `{}` failed with status `{}`", url, status)
+        }
     }
 }
 
@@ -34,3 +121,86 @@ impl<'a> From<Cow<'a, str>> for Error {
         error.into()
     }
 }
+
+/// Location of an error in a WSDL document, lines and columns start at one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourcePosition {
+    pub file: Option<String>,
+    pub line: u64,
+    pub column: u64,
+}
+
+impl SourcePosition {
+    pub fn new(line: u64, column: u64) -> SourcePosition {
+        SourcePosition {
+            file: None,
+            line,
+            column,
+        }
+    }
+}
+
+impl fmt::Display for SourcePosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.file {
+            Some(ref file) => write!(f, "{}:{}:{}", file, self.line, self.column),
+            None => write!(f, "{}:{}", self.line, self.column),
+        }
+    }
+}
+
+impl StdError for SourcePosition {
+    fn description(&self) -> &str {
+        "source position"
+    }
+}
+
+/// Position of an error recorded as its cause, in front of the cause the
+/// error already had.
+#[derive(Debug)]
+struct Located {
+    position: SourcePosition,
+    cause: Option<Box<dyn StdError + Send>>,
+}
+
+impl fmt::Display for Located {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.position.fmt(f)
+    }
+}
+
+impl StdError for Located {
+    fn description(&self) -> &str {
+        "source position"
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        self.cause.as_ref().map(|cause| &**cause as &dyn StdError)
+    }
+}
+
+impl Error {
+    /// Position in the source document the error was raised at, if known.
+    pub fn position(&self) -> Option<&SourcePosition> {
+        self.1
+            .next_error
+            .as_ref()
+            .and_then(|e| e.downcast_ref::<Located>())
+            .map(|located| &located.position)
+    }
+
+    /// Records `position` as the cause of the error, the cause it already had
+    /// follows it in the chain. Errors that have a position keep it.
+    pub(crate) fn at(self, position: SourcePosition) -> Error {
+        if self.position().is_some() {
+            return self;
+        }
+
+        let Error(kind, state) = self;
+        Error::with_chain(Located {
+                              position,
+                              cause: state.next_error,
+                          },
+                          kind)
+    }
+}
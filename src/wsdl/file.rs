@@ -9,6 +9,53 @@ pub fn load(location: &str) -> Result<Vec<u8>> {
     Ok(bytes)
 }
 
+/// Path of a document in the `examples` directory, for tests.
+#[cfg(test)]
+pub fn example(name: &str) -> String {
+    let mut path = ::std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    path.push("examples");
+    path.push(name);
+
+    path.to_str().map(String::from).unwrap()
+}
+
+/// Directory of a test, unique to the process and removed on drop so that
+/// concurrent runs do not share files.
+#[cfg(test)]
+pub struct TempDir(::std::path::PathBuf);
+
+#[cfg(test)]
+impl TempDir {
+    pub fn new(name: &str) -> TempDir {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static DIRECTORIES: AtomicUsize = AtomicUsize::new(0);
+
+        let path = ::std::env::temp_dir().join(format!("soap-rs-{}-{}-{}",
+                                                       name,
+                                                       ::std::process::id(),
+                                                       DIRECTORIES.fetch_add(1, Ordering::SeqCst)));
+        ::std::fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+}
+
+#[cfg(test)]
+impl ::std::ops::Deref for TempDir {
+    type Target = ::std::path::Path;
+
+    fn deref(&self) -> &::std::path::Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = ::std::fs::remove_dir_all(&self.0);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,207 @@
+use super::errors::*;
+use super::import::Importer;
+use super::resolver::Resolver;
+use super::schema::*;
+
+impl Wsdl {
+    /// Loads a document with every imported WSDL and XML Schema document
+    /// inlined, so that `to_xml` writes it as a single self-contained file.
+    /// Prefixes of the imported documents that clash with the ones of the
+    /// entry point are renamed on writing.
+    ///
+    /// WSDL 1.1 components share the target namespace of their document and
+    /// are not renamed, so only the types of documents imported from another
+    /// target namespace can be inlined. Their messages, port types, bindings
+    /// and services fail with `ForeignComponent`.
+    pub fn load_flattened<R: Resolver>(location: &str, resolver: &R) -> Result<Wsdl> {
+        let mut wsdl = Importer::new(resolver).inline_schemas().load(location)?;
+        wsdl.flatten()?;
+        Ok(wsdl)
+    }
+
+    fn flatten(&mut self) -> Result<()> {
+        let target_namespace = self.target_namespace.clone();
+        {
+            let foreign = |kind: &str, name: &str, namespace: &Option<String>| -> Result<()> {
+                if *namespace == target_namespace {
+                    return Ok(());
+                }
+                Err(ErrorKind::ForeignComponent(kind.to_string(),
+                                                name.to_string(),
+                                                namespace.clone().unwrap_or_default())
+                            .into())
+            };
+
+            for message in &self.messages {
+                foreign("Message", &message.name, &message.target_namespace)?;
+            }
+            for port_type in &self.port_types {
+                foreign("Port type", &port_type.name, &port_type.target_namespace)?;
+            }
+            for binding in &self.bindings {
+                foreign("Binding", &binding.name, &binding.target_namespace)?;
+            }
+            for service in &self.services {
+                foreign("Service", &service.name, &service.target_namespace)?;
+            }
+        }
+
+        // Schemas of imported documents are gathered in a single types section.
+        let mut types = self.types.drain(..);
+        if let Some(mut first) = types.next() {
+            for other in types {
+                first.schemas.extend(other.schemas);
+                first.models.extend(other.models);
+            }
+            first.target_namespace = target_namespace;
+            first.imported_from = None;
+            self.types = vec![first];
+        }
+
+        // Imported components are written along with the local ones.
+        for message in &mut self.messages {
+            message.imported_from = None;
+        }
+        for port_type in &mut self.port_types {
+            port_type.imported_from = None;
+        }
+        for binding in &mut self.bindings {
+            binding.imported_from = None;
+        }
+        for service in &mut self.services {
+            service.imported_from = None;
+        }
+
+        self.imports.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::resolver::MemoryResolver;
+    use super::*;
+
+    const SERVICE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/"
+                  xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+                  xmlns:t="urn:service" targetNamespace="urn:service">
+    <wsdl:import namespace="urn:service" location="abstract/Abstract.wsdl"/>
+    <wsdl:binding name="Binding" type="t:PortType">
+        <soap:binding transport="http://schemas.xmlsoap.org/soap/http"/>
+    </wsdl:binding>
+    <wsdl:service name="Service">
+        <wsdl:port name="Port" binding="t:Binding">
+            <soap:address location="http://example.com"/>
+        </wsdl:port>
+    </wsdl:service>
+</wsdl:definitions>
+"#;
+
+    const ABSTRACT: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/"
+                  xmlns:t="urn:types" xmlns:s="urn:service" targetNamespace="urn:service">
+    <wsdl:import namespace="urn:session" location="../xsd/Session.xsd"/>
+    <wsdl:types>
+        <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:types">
+            <xs:include schemaLocation="../xsd/Request.xsd"/>
+        </xs:schema>
+    </wsdl:types>
+    <wsdl:message name="Request">
+        <wsdl:part name="body" element="t:Request"/>
+    </wsdl:message>
+    <wsdl:portType name="PortType">
+        <wsdl:operation name="Ping">
+            <wsdl:input message="s:Request"/>
+        </wsdl:operation>
+    </wsdl:portType>
+</wsdl:definitions>
+"#;
+
+    const REQUEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns:c="urn:common" targetNamespace="urn:types">
+    <xs:import namespace="urn:common" schemaLocation="Common.xsd"/>
+    <xs:element name="Request" type="c:Text"/>
+</xs:schema>
+"#;
+
+    const COMMON: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:common">
+    <xs:include schemaLocation="Request.xsd"/>
+    <xs:simpleType name="Text"><xs:restriction base="xs:string"/></xs:simpleType>
+</xs:schema>
+"#;
+
+    const SESSION: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:session">
+    <xs:element name="Session" type="xs:string"/>
+</xs:schema>
+"#;
+
+    fn resolver() -> MemoryResolver {
+        let mut resolver = MemoryResolver::new();
+        resolver.insert("/wsdl/Service.wsdl", SERVICE).unwrap();
+        resolver.insert("/wsdl/abstract/Abstract.wsdl", ABSTRACT).unwrap();
+        resolver.insert("/wsdl/xsd/Request.xsd", REQUEST).unwrap();
+        resolver.insert("/wsdl/xsd/Common.xsd", COMMON).unwrap();
+        resolver.insert("/wsdl/xsd/Session.xsd", SESSION).unwrap();
+        resolver
+    }
+
+    #[test]
+    fn inlines_imports_and_includes_test() {
+        let wsdl = Wsdl::load_flattened("/wsdl/Service.wsdl", &resolver()).unwrap();
+
+        assert!(wsdl.imports.is_empty());
+        assert_eq!(1, wsdl.types.len());
+        let namespaces = wsdl.types[0]
+            .schemas
+            .iter()
+            .map(|s| s.attribute("targetNamespace").unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["urn:types", "urn:common", "urn:session"], namespaces);
+
+        let written = wsdl.to_xml();
+        assert!(!written.contains("schemaLocation"));
+        assert!(!written.contains("<wsdl:import"));
+        assert!(written.contains("<xs:element xmlns:c=\"urn:common\" name=\"Request\" \
+                                  type=\"c:Text\"/>"));
+        // The `t` prefix of the imported document is bound to another namespace.
+        assert!(written.contains("xmlns:t1=\"urn:types\""));
+        assert!(written.contains("<wsdl:part name=\"body\" element=\"t1:Request\"/>"));
+
+        let reread = Wsdl::parse(written.as_bytes()).unwrap();
+        assert_eq!(1, reread.messages.len());
+        assert_eq!(3, reread.types[0].schemas.len());
+        assert_eq!(Some("urn:types".to_string()),
+                   reread.messages[0].parts[0].element.as_ref().unwrap().namespace);
+    }
+
+    #[test]
+    fn rejects_imports_from_other_namespaces_test() {
+        let mut resolver = resolver();
+        resolver
+            .insert("/wsdl/abstract/Abstract.wsdl",
+                    ABSTRACT.replace("targetNamespace=\"urn:service\"",
+                                     "targetNamespace=\"urn:abstract\""))
+            .unwrap();
+
+        match Wsdl::load_flattened("/wsdl/Service.wsdl", &resolver) {
+            Err(Error(ErrorKind::ForeignComponent(ref kind, ref name, _), _)) => {
+                assert_eq!(("Message", "Request"), (kind.as_str(), name.as_str()))
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+
+        // Without components of its own, only the types are inlined.
+        let types_only = ABSTRACT
+            .replace("targetNamespace=\"urn:service\"", "targetNamespace=\"urn:abstract\"")
+            .replace("<wsdl:message name=\"Request\">", "<!--")
+            .replace("</wsdl:portType>", "-->");
+        resolver.insert("/wsdl/abstract/Abstract.wsdl", types_only).unwrap();
+        let wsdl = Wsdl::load_flattened("/wsdl/Service.wsdl", &resolver).unwrap();
+        assert!(wsdl.messages.is_empty() && wsdl.port_types.is_empty());
+        assert_eq!(3, wsdl.types[0].schemas.len());
+    }
+}
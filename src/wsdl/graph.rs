@@ -0,0 +1,394 @@
+use super::errors::*;
+use super::import::NS_XSD;
+use super::schema::*;
+use super::schema_set::SchemaSet;
+use super::xsd::{XsdComplexType, XsdElement, XsdSimpleType};
+
+use xml::name::OwnedName;
+
+/// Services of a `Wsdl` with every QName reference resolved, from the ports
+/// down to the messages of each bound operation and the schema elements and
+/// types of their parts. Built-in XML Schema types have no definition to link.
+///
+/// References that are dangling or ambiguous are reported in `errors` and the
+/// component holding them is left out of the graph.
+#[derive(Debug)]
+pub struct ServiceGraph<'a> {
+    pub services: Vec<LinkedService<'a>>,
+    pub errors: Vec<ErrorKind>,
+}
+
+#[derive(Debug)]
+pub struct LinkedService<'a> {
+    pub service: &'a WsdlService,
+    pub ports: Vec<LinkedPort<'a>>,
+}
+
+#[derive(Debug)]
+pub struct LinkedPort<'a> {
+    pub port: &'a WsdlPort,
+    pub binding: &'a WsdlBinding,
+    pub port_type: &'a WsdlPortType,
+    pub operations: Vec<LinkedOperation<'a>>,
+}
+
+#[derive(Debug)]
+pub struct LinkedOperation<'a> {
+    pub binding: &'a WsdlOperationBinding,
+    pub operation: &'a WsdlOperation,
+    pub input: Option<LinkedMessage<'a>>,
+    pub output: Option<LinkedMessage<'a>>,
+    pub faults: Vec<LinkedMessage<'a>>,
+}
+
+/// Input, output or fault of an operation. WSDL 2.0 operations refer to a
+/// schema element directly and have no `message`.
+#[derive(Debug)]
+pub struct LinkedMessage<'a> {
+    pub reference: &'a WsdlOperationMessage,
+    pub message: Option<&'a WsdlMessage>,
+    /// Parts of the referenced message, empty for WSDL 2.0 operations.
+    pub parts: Vec<LinkedPart<'a>>,
+}
+
+/// Message part with the schema component it refers to.
+#[derive(Debug)]
+pub struct LinkedPart<'a> {
+    pub part: &'a WsdlMessagePart,
+    pub element: Option<&'a XsdElement>,
+    pub complex_type: Option<&'a XsdComplexType>,
+    pub simple_type: Option<&'a XsdSimpleType>,
+}
+
+impl<'a> ServiceGraph<'a> {
+    pub fn new(wsdl: &'a Wsdl) -> ServiceGraph<'a> {
+        let schemas = wsdl.schema_set();
+        let mut errors = Vec::new();
+        let services = wsdl.services
+            .iter()
+            .map(|service| {
+                     LinkedService {
+                         service,
+                         ports: service
+                             .ports
+                             .iter()
+                             .filter_map(|port| link_port(wsdl, &schemas, port, &mut errors))
+                             .collect(),
+                     }
+                 })
+            .collect();
+
+        ServiceGraph { services, errors }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn find_service(&self, name: &str) -> Option<&LinkedService<'a>> {
+        self.services.iter().find(|s| s.service.name == name)
+    }
+}
+
+impl<'a> LinkedService<'a> {
+    pub fn find_port(&self, name: &str) -> Option<&LinkedPort<'a>> {
+        self.ports.iter().find(|p| p.port.name == name)
+    }
+}
+
+impl<'a> LinkedPort<'a> {
+    pub fn find_operation(&self, name: &str) -> Option<&LinkedOperation<'a>> {
+        self.operations.iter().find(|o| o.operation.name == name)
+    }
+}
+
+impl<'a> LinkedMessage<'a> {
+    pub fn find_part(&self, name: &str) -> Option<&LinkedPart<'a>> {
+        self.parts.iter().find(|p| p.part.name == name)
+    }
+}
+
+fn link_port<'a>(wsdl: &'a Wsdl,
+                 schemas: &SchemaSet<'a>,
+                 port: &'a WsdlPort,
+                 errors: &mut Vec<ErrorKind>)
+                 -> Option<LinkedPort<'a>> {
+    let binding = resolve(&wsdl.bindings,
+                          "Binding",
+                          &port.binding,
+                          &port.name,
+                          errors,
+                          |b| is_named(&port.binding, &b.target_namespace, &b.name))?;
+    let port_type = resolve(&wsdl.port_types,
+                            "Port type",
+                            &binding.port_type,
+                            &binding.name,
+                            errors,
+                            |p| is_named(&binding.port_type, &p.target_namespace, &p.name))?;
+
+    let operations = binding
+        .operations
+        .iter()
+        .filter_map(|operation| {
+                        link_operation(wsdl, schemas, binding, port_type, operation, errors)
+                    })
+        .collect();
+
+    Some(LinkedPort {
+             port,
+             binding,
+             port_type,
+             operations,
+         })
+}
+
+fn link_operation<'a>(wsdl: &'a Wsdl,
+                      schemas: &SchemaSet<'a>,
+                      binding: &'a WsdlBinding,
+                      port_type: &'a WsdlPortType,
+                      operation_binding: &'a WsdlOperationBinding,
+                      errors: &mut Vec<ErrorKind>)
+                      -> Option<LinkedOperation<'a>> {
+    let input_name = operation_binding.input.as_ref().and_then(|i| i.name.as_ref());
+    let output_name = operation_binding.output.as_ref().and_then(|o| o.name.as_ref());
+    let name = OwnedName::local(operation_binding.name.as_str());
+
+    // Overloaded operations are told apart by the names of their input and output.
+    let operation = resolve(&port_type.operations,
+                            "Operation",
+                            &name,
+                            &binding.name,
+                            errors,
+                            |o| {
+        o.name == operation_binding.name &&
+        (input_name.is_none() || o.input.as_ref().and_then(|i| i.name.as_ref()) == input_name) &&
+        (output_name.is_none() || o.output.as_ref().and_then(|o| o.name.as_ref()) == output_name)
+    })?;
+
+    let mut link_message = |reference: &'a WsdlOperationMessage| {
+        let message = match reference.message {
+            Some(ref message) => {
+                Some(resolve(&wsdl.messages,
+                             "Message",
+                             message,
+                             &operation.name,
+                             errors,
+                             |m| is_named(message, &m.target_namespace, &m.name))?)
+            }
+            None => None,
+        };
+
+        let parts = message
+            .map(|m| &m.parts[..])
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(|part| link_part(schemas, part, errors))
+            .collect();
+
+        Some(LinkedMessage {
+                 reference,
+                 message,
+                 parts,
+             })
+    };
+
+    let input = operation.input.as_ref().and_then(&mut link_message);
+    let output = operation.output.as_ref().and_then(&mut link_message);
+    let faults = operation.faults.iter().filter_map(&mut link_message).collect();
+
+    Some(LinkedOperation {
+             binding: operation_binding,
+             operation,
+             input,
+             output,
+             faults,
+         })
+}
+
+/// Links a part to the element or type it refers to, parts whose component is
+/// not defined are left out.
+fn link_part<'a>(schemas: &SchemaSet<'a>,
+                 part: &'a WsdlMessagePart,
+                 errors: &mut Vec<ErrorKind>)
+                 -> Option<LinkedPart<'a>> {
+    let mut linked = LinkedPart {
+        part,
+        element: None,
+        complex_type: None,
+        simple_type: None,
+    };
+
+    if let Some(ref name) = part.element {
+        linked.element = schemas.find_element(name);
+        if linked.element.is_none() {
+            errors.push(ErrorKind::UnresolvedReference("Element".to_string(),
+                                                       name.to_string(),
+                                                       part.name.clone()));
+            return None;
+        }
+    }
+
+    if let Some(ref name) = part.part_type {
+        if name.namespace.as_deref() == Some(NS_XSD) {
+            return Some(linked);
+        }
+
+        linked.complex_type = schemas.find_complex_type(name);
+        linked.simple_type = schemas.find_simple_type(name);
+        if linked.complex_type.is_none() && linked.simple_type.is_none() {
+            errors.push(ErrorKind::UnresolvedReference("Type".to_string(),
+                                                       name.to_string(),
+                                                       part.name.clone()));
+            return None;
+        }
+    }
+
+    Some(linked)
+}
+
+fn resolve<'a, T, F>(items: &'a [T],
+                     kind: &str,
+                     name: &OwnedName,
+                     referrer: &str,
+                     errors: &mut Vec<ErrorKind>,
+                     matches: F)
+                     -> Option<&'a T>
+    where F: Fn(&T) -> bool
+{
+    let mut found = items.iter().filter(|item| matches(item));
+
+    match (found.next(), found.next()) {
+        (Some(item), None) => Some(item),
+        (Some(_), Some(_)) => {
+            errors.push(ErrorKind::AmbiguousReference(kind.to_string(),
+                                                      name.to_string(),
+                                                      referrer.to_string()));
+            None
+        }
+        (None, _) => {
+            errors.push(ErrorKind::UnresolvedReference(kind.to_string(),
+                                                       name.to_string(),
+                                                       referrer.to_string()));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_hello_service_test() {
+        let mut location = ::std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        location.push("examples/hello_world/hello.wsdl");
+        let wsdl = Wsdl::load_from_file(location.to_str().unwrap()).unwrap();
+
+        let graph = ServiceGraph::new(&wsdl);
+
+        assert!(graph.is_valid(), "{:?}", graph.errors);
+        let port = &graph.services[0].ports[0];
+        let operation = &port.operations[0];
+        assert_eq!(port.port_type.name, port.binding.port_type.local_name);
+        assert_eq!(operation.binding.name, operation.operation.name);
+        let input = operation.input.as_ref().unwrap();
+        assert!(!input.parts.is_empty());
+        assert!(input.parts.iter().all(|p| p.complex_type.is_none() && p.simple_type.is_none()));
+    }
+
+    #[test]
+    fn reports_dangling_and_ambiguous_references_test() {
+        let wsdl = Wsdl::parse(r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/"
+                  xmlns:tns="urn:test" targetNamespace="urn:test">
+    <wsdl:portType name="PortType">
+        <wsdl:operation name="Ping">
+            <wsdl:input message="tns:MissingRequest"/>
+        </wsdl:operation>
+    </wsdl:portType>
+    <wsdl:portType name="Duplicate"/>
+    <wsdl:portType name="Duplicate"/>
+    <wsdl:binding name="Binding" type="tns:PortType">
+        <wsdl:operation name="Ping"/>
+        <wsdl:operation name="Pong"/>
+    </wsdl:binding>
+    <wsdl:binding name="DuplicateBinding" type="tns:Duplicate"/>
+    <wsdl:service name="Service">
+        <wsdl:port name="Port" binding="tns:Binding"/>
+        <wsdl:port name="Dangling" binding="tns:MissingBinding"/>
+        <wsdl:port name="Ambiguous" binding="tns:DuplicateBinding"/>
+    </wsdl:service>
+</wsdl:definitions>
+"#
+                                       .as_bytes())
+                .unwrap();
+
+        let graph = ServiceGraph::new(&wsdl);
+        let service = graph.find_service("Service").unwrap();
+
+        assert_eq!(1, service.ports.len());
+        let operation = service.find_port("Port").unwrap().find_operation("Ping").unwrap();
+        assert!(operation.input.is_none());
+
+        let errors = graph.errors.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(vec!["Message `{urn:test}tns:MissingRequest` referenced from `Ping` is not defined",
+                        "Operation `Pong` referenced from `Binding` is not defined",
+                        "Binding `{urn:test}tns:MissingBinding` referenced from `Dangling` is not defined",
+                        "Port type `{urn:test}tns:Duplicate` referenced from `DuplicateBinding` is \
+                         defined more than once"],
+                   errors);
+    }
+
+    #[test]
+    fn links_message_parts_to_schema_components_test() {
+        let wsdl = Wsdl::parse(r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/"
+                  xmlns:xs="http://www.w3.org/2001/XMLSchema"
+                  xmlns:tns="urn:test" targetNamespace="urn:test">
+    <wsdl:types>
+        <xs:schema targetNamespace="urn:test">
+            <xs:element name="Ping" type="xs:string"/>
+            <xs:simpleType name="Code">
+                <xs:restriction base="xs:string"/>
+            </xs:simpleType>
+        </xs:schema>
+    </wsdl:types>
+    <wsdl:message name="Request">
+        <wsdl:part name="body" element="tns:Ping"/>
+        <wsdl:part name="code" type="tns:Code"/>
+        <wsdl:part name="comment" type="xs:string"/>
+        <wsdl:part name="header" element="tns:Header"/>
+    </wsdl:message>
+    <wsdl:portType name="PortType">
+        <wsdl:operation name="Ping">
+            <wsdl:input message="tns:Request"/>
+        </wsdl:operation>
+    </wsdl:portType>
+    <wsdl:binding name="Binding" type="tns:PortType">
+        <wsdl:operation name="Ping"/>
+    </wsdl:binding>
+    <wsdl:service name="Service">
+        <wsdl:port name="Port" binding="tns:Binding"/>
+    </wsdl:service>
+</wsdl:definitions>
+"#
+                                       .as_bytes())
+                .unwrap();
+
+        let graph = ServiceGraph::new(&wsdl);
+        let operation = graph.services[0].ports[0].find_operation("Ping").unwrap();
+        let input = operation.input.as_ref().unwrap();
+
+        assert_eq!(3, input.parts.len());
+        assert_eq!("Ping", input.find_part("body").unwrap().element.unwrap().name);
+        let code = input.find_part("code").unwrap();
+        assert_eq!(Some("Code"), code.simple_type.unwrap().name.as_deref());
+        let comment = input.find_part("comment").unwrap();
+        assert!(comment.complex_type.is_none() && comment.simple_type.is_none());
+        assert!(input.find_part("header").is_none());
+
+        let errors = graph.errors.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        assert_eq!(vec!["Element `{urn:test}tns:Header` referenced from `header` is not defined"],
+                   errors);
+    }
+}
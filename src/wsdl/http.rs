@@ -1,19 +1,66 @@
 use std::io::Read;
 use hyper::Client;
+use hyper::client::Response;
+use hyper::header::Headers;
+use hyper::status::StatusCode;
 use super::errors::*;
+use super::resolver::{Fetched, Validators};
 
-// TODO: Some notes about http data transfering
-// What if someone want to use another client?
-// What about async/sync client does not matter?
+/// Blocking GET used by `HttpResolver`, other clients can be plugged in by
+/// implementing `Resolver`. Responses without a success status are errors,
+/// their body is an error page rather than the document.
 pub fn get(url: &str) -> Result<Vec<u8>> {
     let client = Client::new();
     let mut bytes = Vec::new();
 
-    client.get(url).send()?.read_to_end(&mut bytes)?;
+    successful(url, client.get(url).send()?)?.read_to_end(&mut bytes)?;
 
     Ok(bytes)
 }
 
+/// Conditional GET, the validators of a cached copy are sent as
+/// `If-None-Match` and `If-Modified-Since`.
+pub fn get_if_modified(url: &str, validators: &Validators) -> Result<Fetched> {
+    let client = Client::new();
+    let mut headers = Headers::new();
+    if let Some(ref etag) = validators.etag {
+        headers.set_raw("If-None-Match", vec![etag.clone().into_bytes()]);
+    }
+    if let Some(ref last_modified) = validators.last_modified {
+        headers.set_raw("If-Modified-Since", vec![last_modified.clone().into_bytes()]);
+    }
+
+    let response = client.get(url).headers(headers).send()?;
+    if response.status == StatusCode::NotModified {
+        return Ok(Fetched::NotModified);
+    }
+    let mut response = successful(url, response)?;
+
+    let mut bytes = Vec::new();
+    response.read_to_end(&mut bytes)?;
+
+    let header = |name: &str| {
+        response
+            .headers
+            .get_raw(name)
+            .and_then(|values| values.first())
+            .map(|value| String::from_utf8_lossy(value).into_owned())
+    };
+    let validators = Validators {
+        etag: header("ETag"),
+        last_modified: header("Last-Modified"),
+    };
+
+    Ok(Fetched::Modified(bytes, validators))
+}
+
+fn successful(url: &str, response: Response) -> Result<Response> {
+    if !response.status.is_success() {
+        bail!(ErrorKind::HttpStatus(url.to_string(), response.status.to_string()));
+    }
+    Ok(response)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,599 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use hyper::Url;
+use xml::name::OwnedName;
+use xml::reader::{EventReader, XmlEvent};
+
+use super::element::{XmlElement, XmlNode};
+use super::errors::*;
+use super::resolver::{is_url, Resolver};
+use super::schema::{decode_contents, find_attribute, Wsdl, WsdlTypes, NS_WSDL};
+use super::wsdl2;
+use super::xsd::{self, XsdSchema};
+
+pub const NS_XSD: &str = "http://www.w3.org/2001/XMLSchema";
+
+/// Follows `wsdl:import` elements relative to the location of the importing
+/// document and merges every imported definition into a single `Wsdl`.
+pub struct Importer<'a, R: 'a> {
+    resolver: &'a R,
+    inline_schemas: bool,
+    resolve_schemas: bool,
+    loading: Vec<String>,
+    loaded: HashSet<String>,
+}
+
+impl<'a, R: Resolver> Importer<'a, R> {
+    pub fn new(resolver: &'a R) -> Importer<'a, R> {
+        Importer {
+            resolver,
+            inline_schemas: false,
+            resolve_schemas: false,
+            loading: Vec::new(),
+            loaded: HashSet::new(),
+        }
+    }
+
+    /// Also loads the XML Schema documents referenced by `xs:include`,
+    /// `xs:import` and `wsdl:import` into the types of the definitions.
+    /// Included components replace their `xs:include`, imported schemas are
+    /// added next to the importing one and lose their `schemaLocation`.
+    pub fn inline_schemas(mut self) -> Importer<'a, R> {
+        self.inline_schemas = true;
+        self
+    }
+
+    /// Also loads the XML Schema documents imported or included by the
+    /// schemas of the types sections and adds their models to the models of
+    /// the section, the schema elements are left as they are. Imports without
+    /// `schemaLocation` are looked up with `Resolver::locate_namespace`.
+    pub fn resolve_schemas(mut self) -> Importer<'a, R> {
+        self.resolve_schemas = true;
+        self
+    }
+
+    pub fn load(&mut self, location: &str) -> Result<Wsdl> {
+        let key = self.resolver.normalize(location)?;
+        self.loaded.insert(key.clone());
+        self.loading.push(key);
+
+        let contents = self.load_location(location)?;
+        let mut wsdl = Wsdl::parse_in_file(&contents[..], location)?;
+        if self.inline_schemas {
+            self.inline_types(&mut wsdl, location)?;
+        }
+        if self.resolve_schemas {
+            for types in &mut wsdl.types {
+                self.resolve_models(&mut types.models, location)?;
+            }
+        }
+        self.resolve(&mut wsdl, location)?;
+
+        self.loading.pop();
+        Ok(wsdl)
+    }
+
+    fn resolve(&mut self, wsdl: &mut Wsdl, base: &str) -> Result<()> {
+        let locations = wsdl.imports
+            .iter()
+            .map(|import| import.location.clone())
+            .collect::<Vec<_>>();
+
+        for location in locations {
+            let target = resolve_location(base, &location)?;
+            let key = self.resolver
+                .normalize(&target)
+                .chain_err(|| ErrorKind::MissingImport(location.clone(), base.to_string()))?;
+
+            if let Some(start) = self.loading.iter().position(|l| *l == key) {
+                let mut chain = self.loading[start..].to_vec();
+                chain.push(key);
+                bail!(ErrorKind::ImportCycle(chain));
+            }
+
+            if self.loaded.contains(&key) {
+                continue;
+            }
+
+            let contents = self.load_location(&target)
+                .chain_err(|| ErrorKind::MissingImport(location.clone(), base.to_string()))?;
+
+            // `wsdl:import` is also used to pull in plain XML Schema documents,
+            // those are left to the types section unless schemas are inlined.
+            if !is_wsdl_definitions(&root_element(&contents)?) {
+                self.loaded.insert(key);
+                if self.inline_schemas {
+                    let mut schemas = Vec::new();
+                    let schema = self.read_schema(&contents, &target, &mut schemas)?;
+                    schemas.insert(0, schema);
+                    wsdl.types.push(WsdlTypes {
+                                        documentation: None,
+                                        target_namespace: wsdl.target_namespace.clone(),
+                                        imported_from: Some(target.clone()),
+                                        models: xsd::read_schemas(&schemas)?,
+                                        schemas,
+                                    });
+                } else if self.resolve_schemas {
+                    let schema = XmlElement::parse_in_file(&contents, &target)?;
+                    let mut models = vec![XsdSchema::read(&schema)?];
+                    self.resolve_models(&mut models, &target)?;
+                    if wsdl.types.is_empty() {
+                        wsdl.types.push(WsdlTypes {
+                                            documentation: None,
+                                            target_namespace: wsdl.target_namespace.clone(),
+                                            imported_from: Some(target.clone()),
+                                            schemas: Vec::new(),
+                                            models: Vec::new(),
+                                        });
+                    }
+                    wsdl.types[0].models.extend(models);
+                }
+                continue;
+            }
+
+            let imported = self.load(&target)?;
+            wsdl.merge_imported(imported, &target);
+        }
+
+        Ok(())
+    }
+
+    fn load_location(&self, location: &str) -> Result<Vec<u8>> {
+        decode_contents(&self.resolver.fetch(location)?)
+    }
+
+    fn inline_types(&mut self, wsdl: &mut Wsdl, base: &str) -> Result<()> {
+        for types in &mut wsdl.types {
+            let mut imported = Vec::new();
+            for schema in &mut types.schemas {
+                self.inline_schema(schema, base, &mut imported)?;
+            }
+            types.schemas.extend(imported);
+            types.models = xsd::read_schemas(&types.schemas)?;
+        }
+
+        Ok(())
+    }
+
+    /// Loads the documents imported or included by `models`, and the ones
+    /// those refer to, and appends their models. `base` is the location of
+    /// the document `models` were read from.
+    fn resolve_models(&mut self, models: &mut Vec<XsdSchema>, base: &str) -> Result<()> {
+        let mut bases = vec![base.to_string(); models.len()];
+
+        let mut i = 0;
+        while i < models.len() {
+            let mut references = Vec::new();
+            for import in &models[i].imports {
+                let location = import
+                    .location
+                    .clone()
+                    .or_else(|| {
+                                 import
+                                     .namespace
+                                     .as_ref()
+                                     .and_then(|ns| self.resolver.locate_namespace(ns))
+                             });
+                if let Some(location) = location {
+                    references.push((location, None));
+                }
+            }
+            for location in &models[i].includes {
+                references.push((location.clone(), Some(models[i].target_namespace.clone())));
+            }
+
+            for (location, including) in references {
+                let target = resolve_location(&bases[i], &location)?;
+                let key = self.resolver
+                    .normalize(&target)
+                    .chain_err(|| ErrorKind::MissingImport(location.clone(), bases[i].clone()))?;
+                // A document without a target namespace is read once per
+                // namespace including it, any other document once. Whether an
+                // included document has one is only known once it is read.
+                let chameleon_key = match including {
+                    Some(Some(ref namespace)) => Some(format!("{} {}", key, namespace)),
+                    _ => None,
+                };
+                if self.loaded.contains(chameleon_key.as_ref().unwrap_or(&key)) {
+                    continue;
+                }
+
+                let contents = self.load_location(&target)
+                    .chain_err(|| ErrorKind::MissingImport(location.clone(), bases[i].clone()))?;
+                let schema = XmlElement::parse_in_file(&contents, &target)?;
+                let first_load = match chameleon_key {
+                    Some(chameleon_key) if schema.attribute("targetNamespace").is_none() => {
+                        self.loaded.insert(chameleon_key)
+                    }
+                    _ => self.loaded.insert(key),
+                };
+                if !first_load {
+                    continue;
+                }
+                let model = match including {
+                    Some(ref namespace) => XsdSchema::read_included(&schema, namespace)?,
+                    None => XsdSchema::read(&schema)?,
+                };
+
+                models.push(model);
+                bases.push(target);
+            }
+
+            i += 1;
+        }
+
+        Ok(())
+    }
+
+    fn read_schema(&mut self,
+                   contents: &[u8],
+                   location: &str,
+                   imported: &mut Vec<XmlElement>)
+                   -> Result<XmlElement> {
+        let mut schema = XmlElement::parse_in_file(contents, location)?;
+        self.inline_schema(&mut schema, location, imported)?;
+        Ok(schema)
+    }
+
+    /// Replaces the includes of `schema` with the included components and
+    /// adds the schemas it imports to `imported`, documents already loaded
+    /// are left out.
+    fn inline_schema(&mut self,
+                     schema: &mut XmlElement,
+                     base: &str,
+                     imported: &mut Vec<XmlElement>)
+                     -> Result<()> {
+        let mut children = Vec::new();
+
+        for child in schema.children.drain(..) {
+            let mut element = match child {
+                XmlNode::Element(element) => element,
+                text => {
+                    children.push(text);
+                    continue;
+                }
+            };
+
+            let kind = match element.name.local_name.as_str() {
+                "include" | "import" if is_xsd(&element.name) => element.name.local_name.clone(),
+                _ => {
+                    children.push(XmlNode::Element(element));
+                    continue;
+                }
+            };
+            let location = match find_attribute("schemaLocation", &element.attributes) {
+                Some(location) => location,
+                None => {
+                    children.push(XmlNode::Element(element));
+                    continue;
+                }
+            };
+
+            let target = resolve_location(base, &location)?;
+            let key = self.resolver
+                .normalize(&target)
+                .chain_err(|| ErrorKind::MissingImport(location.clone(), base.to_string()))?;
+            let contents = if self.loaded.insert(key) {
+                Some(self.load_location(&target)
+                         .chain_err(|| ErrorKind::MissingImport(location.clone(), base.to_string()))?)
+            } else {
+                None
+            };
+
+            if kind == "import" {
+                element
+                    .attributes
+                    .retain(|a| a.name.namespace.is_some() || a.name.local_name != "schemaLocation");
+                children.push(XmlNode::Element(element));
+
+                if let Some(contents) = contents {
+                    let schema = self.read_schema(&contents, &target, imported)?;
+                    imported.push(schema);
+                }
+            } else if let Some(contents) = contents {
+                let included = self.read_schema(&contents, &target, imported)?;
+                for child in included.children {
+                    children.push(match child {
+                        XmlNode::Element(mut element) => {
+                            // Declarations of the included schema element move to its children.
+                            for (prefix, uri) in &included.namespaces {
+                                if schema.namespaces.get(prefix) == Some(uri) {
+                                    continue;
+                                }
+                                element
+                                    .namespaces
+                                    .entry(prefix.clone())
+                                    .or_insert_with(|| uri.clone());
+                            }
+                            XmlNode::Element(element)
+                        }
+                        text => text,
+                    });
+                }
+            }
+        }
+
+        schema.children = children;
+        Ok(())
+    }
+}
+
+/// Resolves `location` against the location of the document that references it.
+/// Absolute URLs and absolute paths are returned as is.
+pub fn resolve_location(base: &str, location: &str) -> Result<String> {
+    if is_url(location) {
+        return Ok(location.to_string());
+    }
+
+    if is_url(base) {
+        let base = Url::parse(base).chain_err(|| ErrorKind::InvalidLocation(base.to_string()))?;
+        let joined = base.join(location)
+            .chain_err(|| ErrorKind::InvalidLocation(location.to_string()))?;
+        return Ok(joined.into_string());
+    }
+
+    let path = match Path::new(base).parent() {
+        Some(dir) => dir.join(location),
+        None => Path::new(location).to_path_buf(),
+    };
+
+    path.to_str()
+        .map(String::from)
+        .ok_or_else(|| ErrorKind::InvalidLocation(location.to_string()).into())
+}
+
+fn root_element(contents: &[u8]) -> Result<OwnedName> {
+    for event in EventReader::new(contents) {
+        if let XmlEvent::StartElement { name, .. } = event? {
+            return Ok(name);
+        }
+    }
+
+    Err(ErrorKind::MissingElement("root".to_string()).into())
+}
+
+pub fn is_xsd(name: &OwnedName) -> bool {
+    name.namespace.as_deref() == Some(NS_XSD)
+}
+
+fn is_wsdl_definitions(name: &OwnedName) -> bool {
+    (name.namespace.as_deref() == Some(NS_WSDL) &&
+     name.local_name == "definitions") || wsdl2::is_description(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::file::TempDir;
+    use super::super::resolver::MemoryResolver;
+    use super::super::xsd::{XsdContent, XsdParticle, XsdTypeRef};
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn resolve_location_test() {
+        assert_eq!("http://example.com/wsdl/types.xsd",
+                   resolve_location("http://example.com/wsdl/service.wsdl", "types.xsd").unwrap());
+        assert_eq!("http://example.com/common/types.xsd",
+                   resolve_location("http://example.com/wsdl/service.wsdl", "../common/types.xsd")
+                       .unwrap());
+        assert_eq!("http://other.com/a.wsdl",
+                   resolve_location("/tmp/service.wsdl", "http://other.com/a.wsdl").unwrap());
+        assert_eq!("/tmp/wsdl/abstract.wsdl",
+                   resolve_location("/tmp/wsdl/service.wsdl", "abstract.wsdl").unwrap());
+    }
+
+    #[test]
+    fn merges_imported_definitions_test() {
+        let dir = TempDir::new("import-merge");
+        write(&dir, "service.wsdl", &definitions("urn:service", r#"
+            <wsdl:import namespace="urn:abstract" location="abstract.wsdl"/>
+            <wsdl:service name="Service"/>"#));
+        write(&dir, "abstract.wsdl", &definitions("urn:abstract", r#"
+            <wsdl:message name="Request"/>
+            <wsdl:portType name="PortType"/>"#));
+
+        let wsdl = Wsdl::load_from_file(dir.join("service.wsdl").to_str().unwrap()).unwrap();
+
+        assert_eq!(1, wsdl.imports.len());
+        assert_eq!(1, wsdl.services.len());
+        assert_eq!(Some("urn:abstract".to_string()), wsdl.messages[0].target_namespace);
+        assert_eq!("PortType", wsdl.port_types[0].name);
+    }
+
+    #[test]
+    fn detects_import_cycle_test() {
+        let dir = TempDir::new("import-cycle");
+        write(&dir, "a.wsdl", &definitions("urn:a",
+                                           r#"<wsdl:import namespace="urn:b" location="b.wsdl"/>"#));
+        write(&dir, "b.wsdl", &definitions("urn:b",
+                                           r#"<wsdl:import namespace="urn:a" location="a.wsdl"/>"#));
+
+        let result = Wsdl::load_from_file(dir.join("a.wsdl").to_str().unwrap());
+
+        match result {
+            Err(Error(ErrorKind::ImportCycle(ref chain), _)) => assert_eq!(3, chain.len()),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_missing_import_test() {
+        let mut location = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        location.push("examples/travel/Air.wsdl");
+
+        let result = Wsdl::load_from_file(location.to_str().unwrap());
+
+        match result {
+            Err(Error(ErrorKind::MissingImport(ref location, _), _)) => {
+                assert_eq!("../SessionContext_v1/SessionContext_v1.xsd", location)
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn loads_imports_from_memory_test() {
+        let mut resolver = MemoryResolver::new();
+        resolver
+            .insert("http://example.com/wsdl/service.wsdl",
+                    definitions("urn:service", r#"
+            <wsdl:import namespace="urn:abstract" location="../common/abstract.wsdl"/>
+            <wsdl:service name="Service"/>"#))
+            .unwrap();
+        resolver
+            .insert("http://example.com/common/abstract.wsdl",
+                    definitions("urn:abstract", r#"<wsdl:portType name="PortType"/>"#))
+            .unwrap();
+
+        let wsdl = Wsdl::load_with_resolver("http://example.com/wsdl/./service.wsdl", &resolver)
+            .unwrap();
+
+        assert_eq!("Service", wsdl.services[0].name);
+        assert_eq!("PortType", wsdl.port_types[0].name);
+        assert!(resolver.fetch("http://example.com/missing.wsdl").is_err());
+    }
+
+    #[test]
+    fn reports_error_position_in_imported_file_test() {
+        let dir = TempDir::new("import-position");
+        write(&dir, "service.wsdl", &definitions("urn:service", r#"
+            <wsdl:import namespace="urn:abstract" location="abstract.wsdl"/>"#));
+        write(&dir, "abstract.wsdl", &definitions("urn:abstract", r#"
+            <wsdl:portType/>"#));
+
+        let error = Wsdl::load_from_file(dir.join("service.wsdl").to_str().unwrap()).unwrap_err();
+        let position = error.position().unwrap();
+
+        assert!(position.file.as_ref().unwrap().ends_with("abstract.wsdl"));
+        assert_eq!((3, 13), (position.line, position.column));
+    }
+
+    #[test]
+    fn decodes_declared_encoding_test() {
+        let dir = TempDir::new("import-encoding");
+        File::create(dir.join("latin1.wsdl"))
+            .unwrap()
+            .write_all(b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>
+<wsdl:definitions xmlns:wsdl=\"http://schemas.xmlsoap.org/wsdl/\">
+    <wsdl:documentation>Caf\xE9</wsdl:documentation>
+</wsdl:definitions>")
+            .unwrap();
+
+        let wsdl = Wsdl::load_from_file(dir.join("latin1.wsdl").to_str().unwrap()).unwrap();
+
+        assert_eq!("Café", wsdl.documentation.unwrap().text);
+    }
+
+    #[test]
+    fn resolves_schema_imports_and_includes_test() {
+        let mut resolver = MemoryResolver::new();
+        resolver
+            .insert("/wsdl/service.wsdl",
+                    definitions("urn:service", r#"
+            <wsdl:types>
+                <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:service">
+                    <xs:import namespace="urn:common" schemaLocation="../xsd/common.xsd"/>
+                    <xs:import namespace="urn:common" schemaLocation="../xsd/types.xsd"/>
+                </xs:schema>
+                <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:other">
+                    <xs:import namespace="urn:common" schemaLocation="../xsd/./common.xsd"/>
+                </xs:schema>
+            </wsdl:types>"#))
+            .unwrap();
+        resolver
+            .insert("/xsd/common.xsd",
+                    r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:common">
+    <xs:include schemaLocation="codes.xsd"/>
+    <xs:include schemaLocation="types.xsd"/>
+</xs:schema>"#)
+            .unwrap();
+        resolver
+            .insert("/xsd/types.xsd",
+                    r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:common">
+    <xs:simpleType name="Amount"><xs:restriction base="xs:decimal"/></xs:simpleType>
+</xs:schema>"#)
+            .unwrap();
+        resolver
+            .insert("/xsd/codes.xsd",
+                    r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+    <xs:simpleType name="Code"><xs:restriction base="xs:string"/></xs:simpleType>
+    <xs:complexType name="Order">
+        <xs:sequence><xs:element name="code" type="Code"/></xs:sequence>
+    </xs:complexType>
+</xs:schema>"#)
+            .unwrap();
+
+        let wsdl = Wsdl::load_with_schemas("/wsdl/service.wsdl", &resolver).unwrap();
+
+        // The document both included and imported is modelled once.
+        assert_eq!(5, wsdl.schemas().len());
+        let code = OwnedName::qualified("Code", "urn:common", None::<&str>);
+        assert!(wsdl.find_simple_type(&code).is_some());
+
+        let order = wsdl.find_complex_type(&OwnedName::qualified("Order", "urn:common", None::<&str>))
+            .unwrap();
+        match order.content {
+            XsdContent::Group(ref group) => {
+                match group.particles[0] {
+                    XsdParticle::Element(ref element) => {
+                        assert_eq!(Some(XsdTypeRef::Named(code)), element.element_type)
+                    }
+                    ref other => panic!("Unexpected particle: {:?}", other),
+                }
+            }
+            ref other => panic!("Unexpected content: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn includes_imported_schema_without_namespace_test() {
+        let mut resolver = MemoryResolver::new();
+        resolver
+            .insert("/wsdl/service.wsdl",
+                    definitions("urn:service", r#"
+            <wsdl:types>
+                <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:service">
+                    <xs:import schemaLocation="../xsd/codes.xsd"/>
+                    <xs:import namespace="urn:common" schemaLocation="../xsd/common.xsd"/>
+                </xs:schema>
+            </wsdl:types>"#))
+            .unwrap();
+        resolver
+            .insert("/xsd/common.xsd",
+                    r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:common">
+    <xs:include schemaLocation="codes.xsd"/>
+</xs:schema>"#)
+            .unwrap();
+        resolver
+            .insert("/xsd/codes.xsd",
+                    r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+    <xs:simpleType name="Code"><xs:restriction base="xs:string"/></xs:simpleType>
+</xs:schema>"#)
+            .unwrap();
+
+        let wsdl = Wsdl::load_with_schemas("/wsdl/service.wsdl", &resolver).unwrap();
+
+        assert_eq!(4, wsdl.schemas().len());
+        assert!(wsdl.find_simple_type(&OwnedName::local("Code")).is_some());
+        let code = OwnedName::qualified("Code", "urn:common", None::<&str>);
+        assert!(wsdl.find_simple_type(&code).is_some());
+    }
+
+    fn definitions(target_namespace: &str, body: &str) -> String {
+        format!(r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/" targetNamespace="{}">{}
+</wsdl:definitions>"#,
+                target_namespace,
+                body)
+    }
+
+    fn write(dir: &Path, name: &str, contents: &str) {
+        File::create(dir.join(name))
+            .unwrap()
+            .write_all(contents.as_bytes())
+            .unwrap();
+    }
+}
@@ -1,4 +1,22 @@
+pub mod bundle;
+pub mod cache;
+pub mod catalog;
+pub mod charset;
+pub mod diagnostics;
+pub mod element;
 pub mod errors;
 pub mod file;
+pub mod flatten;
+pub mod graph;
 pub mod http;
+pub mod import;
+pub mod pattern;
+pub mod reader;
+pub mod resolver;
 pub mod schema;
+pub mod schema_set;
+pub mod soap;
+pub mod validation;
+pub mod wsdl2;
+pub mod writer;
+pub mod xsd;
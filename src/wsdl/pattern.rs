@@ -0,0 +1,658 @@
+use super::errors::*;
+
+use std::mem;
+
+/// Regular expression of an XML Schema `pattern` facet. Patterns match the
+/// whole value, there are no anchors. Character class subtraction and the
+/// `\p{..}` general categories are supported, Unicode block escapes are not.
+///
+/// Patterns compile to an automaton that is run on all of its states at
+/// once, matching takes time linear in the length of the value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    states: Vec<State>,
+    start: usize,
+}
+
+/// States a pattern may compile to, counted repetitions are expanded.
+const MAX_STATES: usize = 100_000;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Atom(CharClass),
+    Group(Vec<Vec<Node>>),
+    Repeat(Box<Node>, usize, Option<usize>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum State {
+    /// Consumes a character of the class and goes on to the given state.
+    Class(CharClass, usize),
+    /// Goes on to both states without consuming anything.
+    Split(usize, usize),
+    Match,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CharClass {
+    negated: bool,
+    items: Vec<ClassItem>,
+    subtracted: Option<Box<CharClass>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ClassItem {
+    Range(char, char),
+    /// Multi-character escape such as `\d`, uppercase letters negate it.
+    Escape(char),
+    Category(String, bool),
+    /// `.`, anything but a line break.
+    Wildcard,
+}
+
+impl Pattern {
+    pub fn new(source: &str) -> Result<Pattern> {
+        let mut parser = Parser {
+            source,
+            chars: source.chars().collect(),
+            position: 0,
+        };
+        let alternatives = parser.alternatives()?;
+        if parser.position < parser.chars.len() {
+            return Err(parser.invalid());
+        }
+
+        let mut compiler = Compiler { states: vec![State::Match] };
+        let start = compiler
+            .alternatives(&alternatives, 0)
+            .ok_or_else(|| parser.invalid())?;
+
+        Ok(Pattern {
+               states: compiler.states,
+               start,
+           })
+    }
+
+    pub fn is_match(&self, value: &str) -> bool {
+        let mut current = Vec::new();
+        let mut next = Vec::new();
+        let mut added = vec![None; self.states.len()];
+        self.add(self.start, 0, &mut current, &mut added);
+
+        for (step, c) in value.chars().enumerate() {
+            for &state in &current {
+                if let State::Class(ref class, to) = self.states[state] {
+                    if class.matches(c) {
+                        self.add(to, step + 1, &mut next, &mut added);
+                    }
+                }
+            }
+            if next.is_empty() {
+                return false;
+            }
+            mem::swap(&mut current, &mut next);
+            next.clear();
+        }
+
+        current.iter().any(|&state| self.states[state] == State::Match)
+    }
+
+    /// Adds `state` and the states it reaches without consuming a character
+    /// to `states`, `added` records the step each state was last added at.
+    fn add(&self, state: usize, step: usize, states: &mut Vec<usize>, added: &mut [Option<usize>]) {
+        let mut pending = vec![state];
+        while let Some(state) = pending.pop() {
+            if added[state] == Some(step) {
+                continue;
+            }
+            added[state] = Some(step);
+
+            match self.states[state] {
+                State::Split(first, second) => {
+                    pending.push(second);
+                    pending.push(first);
+                }
+                _ => states.push(state),
+            }
+        }
+    }
+}
+
+/// Builds the states of a pattern from its end, each node is compiled with
+/// the state that follows it. `None` when the pattern has too many states.
+struct Compiler {
+    states: Vec<State>,
+}
+
+impl Compiler {
+    fn push(&mut self, state: State) -> Option<usize> {
+        if self.states.len() >= MAX_STATES {
+            return None;
+        }
+        self.states.push(state);
+        Some(self.states.len() - 1)
+    }
+
+    fn alternatives(&mut self, alternatives: &[Vec<Node>], next: usize) -> Option<usize> {
+        let mut entries = Vec::new();
+        for sequence in alternatives {
+            entries.push(self.sequence(sequence, next)?);
+        }
+
+        let mut entry = entries.pop()?;
+        while let Some(other) = entries.pop() {
+            entry = self.push(State::Split(other, entry))?;
+        }
+        Some(entry)
+    }
+
+    fn sequence(&mut self, nodes: &[Node], next: usize) -> Option<usize> {
+        let mut entry = next;
+        for node in nodes.iter().rev() {
+            entry = self.node(node, entry)?;
+        }
+        Some(entry)
+    }
+
+    fn node(&mut self, node: &Node, next: usize) -> Option<usize> {
+        match *node {
+            Node::Atom(ref class) => self.push(State::Class(class.clone(), next)),
+            Node::Group(ref alternatives) => self.alternatives(alternatives, next),
+            Node::Repeat(ref node, min, max) => {
+                let mut entry = match max {
+                    // Optional repetitions, each one may end the repeat.
+                    Some(max) => {
+                        let mut entry = next;
+                        for _ in min..max {
+                            let repetition = self.node(node, entry)?;
+                            entry = self.push(State::Split(repetition, next))?;
+                        }
+                        entry
+                    }
+                    None => {
+                        let repeat = self.push(State::Split(next, next))?;
+                        let repetition = self.node(node, repeat)?;
+                        self.states[repeat] = State::Split(repetition, next);
+                        repeat
+                    }
+                };
+                for _ in 0..min {
+                    // Nodes without states, such as `()`, compile the same
+                    // way every time, their count is not bounded by the
+                    // number of states.
+                    let states = self.states.len();
+                    entry = self.node(node, entry)?;
+                    if self.states.len() == states {
+                        break;
+                    }
+                }
+                Some(entry)
+            }
+        }
+    }
+}
+
+impl CharClass {
+    fn single(item: ClassItem) -> CharClass {
+        CharClass {
+            negated: false,
+            items: vec![item],
+            subtracted: None,
+        }
+    }
+
+    fn matches(&self, c: char) -> bool {
+        let matched = self.items.iter().any(|item| item.matches(c)) != self.negated;
+        matched && !self.subtracted.as_ref().map_or(false, |s| s.matches(c))
+    }
+}
+
+impl ClassItem {
+    fn matches(&self, c: char) -> bool {
+        match *self {
+            ClassItem::Range(from, to) => from <= c && c <= to,
+            ClassItem::Escape(escape) => {
+                let matched = match escape.to_ascii_lowercase() {
+                    'd' => general_category(c) == "Nd",
+                    's' => c == ' ' || c == '\t' || c == '\n' || c == '\r',
+                    'w' => !general_category(c).starts_with(&['P', 'Z', 'C'][..]),
+                    'i' => c.is_alphabetic() || c == '_' || c == ':',
+                    _ => c.is_alphanumeric() || ".-_:".contains(c),
+                };
+                matched != escape.is_uppercase()
+            }
+            ClassItem::Category(ref category, negated) => {
+                let actual = general_category(c);
+                let matched = if category.len() == 1 {
+                    actual.starts_with(category.as_str())
+                } else {
+                    actual == category
+                };
+                matched != negated
+            }
+            ClassItem::Wildcard => c != '\n' && c != '\r',
+        }
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn invalid(&self) -> Error {
+        ErrorKind::InvalidPattern(self.source.to_string()).into()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).cloned()
+    }
+
+    fn next(&mut self) -> Result<char> {
+        let c = self.peek().ok_or_else(|| self.invalid())?;
+        self.position += 1;
+        Ok(c)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        if self.next()? != expected {
+            return Err(self.invalid());
+        }
+        Ok(())
+    }
+
+    fn alternatives(&mut self) -> Result<Vec<Vec<Node>>> {
+        let mut alternatives = vec![self.sequence()?];
+        while self.peek() == Some('|') {
+            self.position += 1;
+            alternatives.push(self.sequence()?);
+        }
+        Ok(alternatives)
+    }
+
+    fn sequence(&mut self) -> Result<Vec<Node>> {
+        let mut nodes = Vec::new();
+
+        while let Some(c) = self.peek() {
+            let atom = match c {
+                '|' | ')' => break,
+                '(' => {
+                    self.position += 1;
+                    let alternatives = self.alternatives()?;
+                    self.expect(')')?;
+                    Node::Group(alternatives)
+                }
+                '[' => {
+                    self.position += 1;
+                    Node::Atom(self.class()?)
+                }
+                '.' => {
+                    self.position += 1;
+                    Node::Atom(CharClass::single(ClassItem::Wildcard))
+                }
+                '\\' => {
+                    self.position += 1;
+                    Node::Atom(CharClass::single(self.escape()?))
+                }
+                '*' | '+' | '?' | '{' | ']' => return Err(self.invalid()),
+                c => {
+                    self.position += 1;
+                    Node::Atom(CharClass::single(ClassItem::Range(c, c)))
+                }
+            };
+            nodes.push(self.quantified(atom)?);
+        }
+
+        Ok(nodes)
+    }
+
+    fn quantified(&mut self, atom: Node) -> Result<Node> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.position += 1;
+                let min = self.number()?;
+                let max = if self.peek() == Some(',') {
+                    self.position += 1;
+                    if self.peek() == Some('}') {
+                        None
+                    } else {
+                        Some(self.number()?)
+                    }
+                } else {
+                    Some(min)
+                };
+                if self.peek() != Some('}') || max.map_or(false, |max| max < min) {
+                    return Err(self.invalid());
+                }
+                (min, max)
+            }
+            _ => return Ok(atom),
+        };
+
+        self.position += 1;
+        Ok(Node::Repeat(Box::new(atom), min, max))
+    }
+
+    fn number(&mut self) -> Result<usize> {
+        let start = self.position;
+        while self.peek().map_or(false, |c| c.is_ascii_digit()) {
+            self.position += 1;
+        }
+        self.chars[start..self.position]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .map_err(|_| self.invalid())
+    }
+
+    /// Escape after a backslash, inside or outside of a character class.
+    fn escape(&mut self) -> Result<ClassItem> {
+        let c = self.next()?;
+        let item = match c {
+            'n' => ClassItem::Range('\n', '\n'),
+            'r' => ClassItem::Range('\r', '\r'),
+            't' => ClassItem::Range('\t', '\t'),
+            'd' | 'D' | 's' | 'S' | 'w' | 'W' | 'i' | 'I' | 'c' | 'C' => ClassItem::Escape(c),
+            'p' | 'P' => {
+                self.expect('{')?;
+                let start = self.position;
+                while self.peek().map_or(false, |c| c != '}') {
+                    self.position += 1;
+                }
+                let category = self.chars[start..self.position].iter().collect::<String>();
+                self.expect('}')?;
+                if !CATEGORIES.contains(&category.as_str()) {
+                    return Err(self.invalid());
+                }
+                ClassItem::Category(category, c == 'P')
+            }
+            '\\' | '|' | '.' | '-' | '^' | '?' | '*' | '+' | '{' | '}' | '(' | ')' | '[' | ']' => {
+                ClassItem::Range(c, c)
+            }
+            _ => return Err(self.invalid()),
+        };
+        Ok(item)
+    }
+
+    /// Character class after its opening bracket.
+    fn class(&mut self) -> Result<CharClass> {
+        let mut class = CharClass {
+            negated: false,
+            items: Vec::new(),
+            subtracted: None,
+        };
+        if self.peek() == Some('^') {
+            self.position += 1;
+            class.negated = true;
+        }
+
+        loop {
+            let c = self.next()?;
+            let item = match c {
+                ']' if !class.items.is_empty() => return Ok(class),
+                '-' if self.peek() == Some('[') && !class.items.is_empty() => {
+                    self.position += 1;
+                    class.subtracted = Some(Box::new(self.class()?));
+                    self.expect(']')?;
+                    return Ok(class);
+                }
+                '\\' => self.escape()?,
+                '[' => return Err(self.invalid()),
+                c => ClassItem::Range(c, c),
+            };
+
+            let item = match item {
+                ClassItem::Range(from, _) if self.peek() == Some('-') &&
+                                             self.chars.get(self.position + 1) != Some(&'[') &&
+                                             self.chars.get(self.position + 1) != Some(&']') => {
+                    self.position += 1;
+                    let to = match self.next()? {
+                        '\\' => {
+                            match self.escape()? {
+                                ClassItem::Range(to, _) => to,
+                                _ => return Err(self.invalid()),
+                            }
+                        }
+                        to => to,
+                    };
+                    if to < from {
+                        return Err(self.invalid());
+                    }
+                    ClassItem::Range(from, to)
+                }
+                item => item,
+            };
+            class.items.push(item);
+        }
+    }
+}
+
+/// General categories of `\p{..}` escapes, one letter ones group the others.
+const CATEGORIES: &[&str] = &["L", "Lu", "Ll", "Lt", "Lm", "Lo", "M", "Mn", "Mc", "Me", "N",
+                              "Nd", "Nl", "No", "P", "Pc", "Pd", "Ps", "Pe", "Pi", "Pf", "Po",
+                              "Z", "Zs", "Zl", "Zp", "S", "Sm", "Sc", "Sk", "So", "C", "Cc",
+                              "Cf", "Co", "Cn"];
+
+/// Unicode general category of `c`, exact for Latin-1 and the fullwidth
+/// forms of ASCII. Beyond that it is derived from the character properties
+/// of the standard library and the ranges of the main punctuation, symbol
+/// and combining mark blocks. Marks of other blocks count as letters,
+/// unassigned characters and symbols outside of these blocks as `So`.
+fn general_category(c: char) -> &'static str {
+    let code = c as u32;
+    if code < 0x100 {
+        return latin1_category(code);
+    }
+    if (0xFF01..=0xFF5E).contains(&code) {
+        return latin1_category(code - 0xFEE0);
+    }
+    if let Some(bracket) = bracket_category(code) {
+        return bracket;
+    }
+
+    match code {
+        0x0300..=0x036F | 0x0483..=0x0487 | 0x0591..=0x05BD | 0x0610..=0x061A |
+        0x064B..=0x065F | 0x1AB0..=0x1ABD | 0x1DC0..=0x1DFF | 0x20D0..=0x20DC | 0x20E1 |
+        0x20E5..=0x20F0 | 0xFE00..=0xFE0F | 0xFE20..=0xFE2F => "Mn",
+        0x0488 | 0x0489 | 0x1ABE | 0x20DD..=0x20E0 | 0x20E2..=0x20E4 => "Me",
+        0x0600..=0x0605 | 0x061C | 0x06DD | 0x200B..=0x200F | 0x202A..=0x202E |
+        0x2060..=0x2064 | 0x2066..=0x206F | 0xFEFF | 0xFFF9..=0xFFFB => "Cf",
+        0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD => "Co",
+        0x2028 => "Zl",
+        0x2029 => "Zp",
+        0x1806 | 0x2010..=0x2015 | 0x2E17 | 0x2E1A | 0x2E3A | 0x2E3B | 0x2E40 | 0x301C |
+        0x3030 | 0x30A0 | 0xFE31 | 0xFE32 | 0xFE58 | 0xFE63 => "Pd",
+        0x2018 | 0x201B | 0x201C | 0x201F | 0x2039 | 0x2E02 | 0x2E04 | 0x2E09 | 0x2E0C |
+        0x2E1C | 0x2E20 => "Pi",
+        0x2019 | 0x201D | 0x203A | 0x2E03 | 0x2E05 | 0x2E0A | 0x2E0D | 0x2E1D | 0x2E21 => "Pf",
+        0x203F | 0x2040 | 0x2054 | 0xFE33 | 0xFE34 | 0xFE4D..=0xFE4F => "Pc",
+        0x037E | 0x0387 | 0x055A..=0x055F | 0x0589 | 0x05C0 | 0x05C3 | 0x05C6 | 0x05F3 |
+        0x05F4 | 0x0609 | 0x060A | 0x060C | 0x060D | 0x061B | 0x061D..=0x061F |
+        0x066A..=0x066D | 0x06D4 | 0x0964 | 0x0965 | 0x0970 | 0x0E4F | 0x0E5A | 0x0E5B |
+        0x10FB | 0x1360..=0x1368 | 0x166E | 0x1800..=0x180A | 0x2016 | 0x2017 |
+        0x2020..=0x2027 | 0x2030..=0x2038 | 0x203B..=0x203E | 0x2041..=0x2043 |
+        0x2047..=0x2051 | 0x2053 | 0x2055..=0x205E | 0x2E00..=0x2E4F | 0x3001..=0x3003 |
+        0x303D | 0x30FB | 0xFE10..=0xFE16 | 0xFE19 | 0xFE30 | 0xFE45 | 0xFE46 |
+        0xFE49..=0xFE4C | 0xFE50..=0xFE57 | 0xFE5F..=0xFE61 | 0xFE68 | 0xFE6A | 0xFE6B |
+        0xFF61 | 0xFF64 | 0xFF65 => "Po",
+        0x058F | 0x060B | 0x09F2 | 0x09F3 | 0x0AF1 | 0x0BF9 | 0x0E3F | 0x17DB |
+        0x20A0..=0x20CF | 0xFDFC | 0xFE69 | 0xFFE0 | 0xFFE1 | 0xFFE5 | 0xFFE6 => "Sc",
+        0x02C2..=0x02C5 | 0x02D2..=0x02DF | 0x02E5..=0x02EB | 0x02ED | 0x02EF..=0x02FF |
+        0x0375 | 0x0384 | 0x0385 | 0x1FBD | 0x1FBF..=0x1FC1 | 0x1FCD..=0x1FCF |
+        0x1FDD..=0x1FDF | 0x1FED..=0x1FEF | 0x1FFD | 0x1FFE | 0x309B | 0x309C |
+        0xA700..=0xA716 | 0xA720 | 0xA721 | 0xA789 | 0xA78A | 0xFFE3 => "Sk",
+        0x03F6 | 0x0606..=0x0608 | 0x2044 | 0x2052 | 0x207A..=0x207C | 0x208A..=0x208C |
+        0x2118 | 0x2140..=0x2144 | 0x214B | 0x2190..=0x2194 | 0x219A | 0x219B | 0x21A0 |
+        0x21A3 | 0x21A6 | 0x21AE | 0x21CE | 0x21CF | 0x21D2 | 0x21D4 | 0x21F4..=0x22FF |
+        0x2320 | 0x2321 | 0x237C | 0x239B..=0x23B3 | 0x23DC..=0x23E1 | 0x25B7 | 0x25C1 |
+        0x25F8..=0x25FF | 0x266F | 0x27C0..=0x27C4 | 0x27C7..=0x27E5 | 0x27F0..=0x27FF |
+        0x2900..=0x2982 | 0x2999..=0x29D7 | 0x29DC..=0x29FB | 0x29FE..=0x2AFF |
+        0x2B30..=0x2B44 | 0x2B47..=0x2B4C | 0xFB29 | 0xFE62 | 0xFE64..=0xFE66 | 0xFFE2 |
+        0xFFE9..=0xFFEC => "Sm",
+        // Enclosed letters, alphabetic but symbols.
+        0x249C..=0x24E9 | 0x1F130..=0x1F149 | 0x1F150..=0x1F169 | 0x1F170..=0x1F189 => "So",
+        _ if c.is_whitespace() => "Zs",
+        _ if c.is_numeric() => number_category(code),
+        _ if c.is_alphabetic() => letter_category(c, code),
+        _ => "So",
+    }
+}
+
+fn latin1_category(code: u32) -> &'static str {
+    match code {
+        0x00..=0x1F | 0x7F..=0x9F => "Cc",
+        0x20 | 0xA0 => "Zs",
+        0x30..=0x39 => "Nd",
+        0x41..=0x5A | 0xC0..=0xD6 | 0xD8..=0xDE => "Lu",
+        0x61..=0x7A | 0xB5 | 0xDF..=0xF6 | 0xF8..=0xFF => "Ll",
+        0xAA | 0xBA => "Lo",
+        0x5F => "Pc",
+        0x2D => "Pd",
+        0x28 | 0x5B | 0x7B => "Ps",
+        0x29 | 0x5D | 0x7D => "Pe",
+        0xAB => "Pi",
+        0xBB => "Pf",
+        0x2B | 0x3C..=0x3E | 0x7C | 0x7E | 0xAC | 0xB1 | 0xD7 | 0xF7 => "Sm",
+        0x24 | 0xA2..=0xA5 => "Sc",
+        0x5E | 0x60 | 0xA8 | 0xAF | 0xB4 | 0xB8 => "Sk",
+        0xA6 | 0xA9 | 0xAE | 0xB0 => "So",
+        0xAD => "Cf",
+        0xB2 | 0xB3 | 0xB9 | 0xBC..=0xBE => "No",
+        // ! " # % & ' * , . / : ; ? @ \ ¡ § ¶ · ¿
+        _ => "Po",
+    }
+}
+
+/// Opening and closing brackets, which alternate within these ranges.
+fn bracket_category(code: u32) -> Option<&'static str> {
+    const BRACKETS: &[(u32, u32)] = &[(0x0F3A, 0x0F3D), (0x169B, 0x169C), (0x2045, 0x2046),
+                                      (0x207D, 0x207E), (0x208D, 0x208E), (0x2308, 0x230B),
+                                      (0x2329, 0x232A), (0x2768, 0x2775), (0x27C5, 0x27C6),
+                                      (0x27E6, 0x27EF), (0x2983, 0x2998), (0x29D8, 0x29DB),
+                                      (0x29FC, 0x29FD), (0x2E22, 0x2E29), (0x3008, 0x3011),
+                                      (0x3014, 0x301B), (0xFE17, 0xFE18), (0xFE35, 0xFE44),
+                                      (0xFE59, 0xFE5E), (0xFF5F, 0xFF60), (0xFF62, 0xFF63)];
+
+    BRACKETS
+        .iter()
+        .find(|&&(first, last)| first <= code && code <= last)
+        .map(|&(first, _)| if (code - first) % 2 == 1 { "Pe" } else { "Ps" })
+}
+
+fn number_category(code: u32) -> &'static str {
+    match code {
+        0x0660..=0x0669 | 0x06F0..=0x06F9 | 0x07C0..=0x07C9 | 0x0966..=0x096F |
+        0x09E6..=0x09EF | 0x0A66..=0x0A6F | 0x0AE6..=0x0AEF | 0x0B66..=0x0B6F |
+        0x0BE6..=0x0BEF | 0x0C66..=0x0C6F | 0x0CE6..=0x0CEF | 0x0D66..=0x0D6F |
+        0x0DE6..=0x0DEF | 0x0E50..=0x0E59 | 0x0ED0..=0x0ED9 | 0x0F20..=0x0F29 |
+        0x1040..=0x1049 | 0x1090..=0x1099 | 0x17E0..=0x17E9 | 0x1810..=0x1819 |
+        0x1946..=0x194F | 0x19D0..=0x19D9 | 0x1A80..=0x1A89 | 0x1A90..=0x1A99 |
+        0x1B50..=0x1B59 | 0x1BB0..=0x1BB9 | 0x1C40..=0x1C49 | 0x1C50..=0x1C59 |
+        0xA620..=0xA629 | 0xA8D0..=0xA8D9 | 0xA900..=0xA909 | 0xA9D0..=0xA9D9 |
+        0xAA50..=0xAA59 | 0xABF0..=0xABF9 | 0x104A0..=0x104A9 | 0x1D7CE..=0x1D7FF => "Nd",
+        0x16EE..=0x16F0 | 0x2160..=0x2182 | 0x2185..=0x2188 | 0x3007 | 0x3021..=0x3029 |
+        0x3038..=0x303A | 0xA6E6..=0xA6EF | 0x10140..=0x10174 => "Nl",
+        _ => "No",
+    }
+}
+
+fn letter_category(c: char, code: u32) -> &'static str {
+    match code {
+        0x01C5 | 0x01C8 | 0x01CB | 0x01F2 | 0x1F88..=0x1F8F | 0x1F98..=0x1F9F |
+        0x1FA8..=0x1FAF | 0x1FBC | 0x1FCC | 0x1FFC => "Lt",
+        0x02B0..=0x02C1 | 0x02C6..=0x02D1 | 0x02E0..=0x02E4 | 0x02EC | 0x02EE | 0x0374 |
+        0x037A | 0x0559 | 0x0640 | 0x06E5 | 0x06E6 | 0x1D2C..=0x1D6A | 0x3005 |
+        0x3031..=0x3035 | 0x309D | 0x309E | 0x30FC..=0x30FE | 0xFF70 | 0xFF9E | 0xFF9F => "Lm",
+        _ if c.is_uppercase() => "Lu",
+        _ if c.is_lowercase() => "Ll",
+        _ => "Lo",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_whole_values_test() {
+        let code = Pattern::new("[A-Z]{2}[0-9]{1,4}").unwrap();
+        assert!(code.is_match("BA123"));
+        assert!(!code.is_match("BA12345"));
+        assert!(!code.is_match("xBA1"));
+
+        let phone = Pattern::new(r"(\+\d{1,3} )?\d+(-\d+)*").unwrap();
+        assert!(phone.is_match("+372 555-1234"));
+        assert!(phone.is_match("5551234"));
+        assert!(!phone.is_match("555--1234"));
+
+        let consonants = Pattern::new("[a-z-[aeiou]]+").unwrap();
+        assert!(consonants.is_match("xyz"));
+        assert!(!consonants.is_match("abc"));
+
+        assert!(Pattern::new(r"\p{Lu}\p{Ll}*|.?").unwrap().is_match("Tallinn"));
+        assert!(Pattern::new("(a*)*b").unwrap().is_match("aaab"));
+    }
+
+    #[test]
+    fn rejects_invalid_patterns_test() {
+        for source in &["[a-", "(ab", "a{3,1}", "*a", r"\p{IsGreek}", r"\q"] {
+            match Pattern::new(source) {
+                Err(Error(ErrorKind::InvalidPattern(ref pattern), _)) => assert_eq!(source, pattern),
+                other => panic!("Unexpected result for `{}`: {:?}", source, other),
+            }
+        }
+    }
+
+    #[test]
+    fn matches_general_categories_test() {
+        let punctuation = Pattern::new(r"\p{P}+").unwrap();
+        assert!(punctuation.is_match("!«—»。¿"));
+        for symbol in &["$", "+", "<", "^", "|", "~"] {
+            assert!(!punctuation.is_match(symbol));
+        }
+
+        assert!(Pattern::new(r"\p{S}+").unwrap().is_match("$+^€→©"));
+        assert!(Pattern::new(r"\p{Sc}+").unwrap().is_match("$€£¥"));
+        assert!(Pattern::new(r"\p{Lt}").unwrap().is_match("ǅ"));
+        assert!(Pattern::new(r"\p{Pd}").unwrap().is_match("–"));
+        assert!(Pattern::new(r"\p{Nd}+").unwrap().is_match("0٣９"));
+        assert!(!Pattern::new(r"\d").unwrap().is_match("½"));
+        assert!(Pattern::new(r"\P{C}+").unwrap().is_match("a b"));
+        assert!(!Pattern::new(r"\p{C}").unwrap().is_match("\u{200B}a"));
+        assert!(Pattern::new(r"\p{Cf}").unwrap().is_match("\u{200B}"));
+        // Unlike in other dialects, `\w` excludes all punctuation.
+        assert!(Pattern::new(r"\w+").unwrap().is_match("Straße1+"));
+        assert!(!Pattern::new(r"\w").unwrap().is_match("_"));
+    }
+
+    #[test]
+    fn matches_long_values_test() {
+        let long = "a".repeat(100_000);
+        assert!(Pattern::new(".*").unwrap().is_match(&long));
+        assert!(!Pattern::new("[a-z]{1,10000}").unwrap().is_match(&long));
+        assert!(!Pattern::new("a*b").unwrap().is_match(&long));
+        // Repetitions are expanded, too many of them are not supported.
+        assert!(Pattern::new("a{1,100000}").is_err());
+    }
+
+    #[test]
+    fn matches_nested_quantifiers_in_linear_time_test() {
+        let value = format!("{}c", "a".repeat(10_000));
+        for source in &["(a|a)*b", "(a*)*b", "(a+)+b", "(a|aa)*(b|c)?d", "((a?){1,20})*b"] {
+            assert!(!Pattern::new(source).unwrap().is_match(&value));
+        }
+        assert!(Pattern::new("(a|a)*c").unwrap().is_match(&value));
+    }
+
+    #[test]
+    fn compiles_large_repetitions_of_empty_groups_test() {
+        assert!(Pattern::new("(){4000000000}").unwrap().is_match(""));
+        assert!(Pattern::new("((){4000000000}){4000000000}a").unwrap().is_match("a"));
+        assert!(Pattern::new("(){0,4000000000}").is_err());
+    }
+}
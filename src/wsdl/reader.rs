@@ -0,0 +1,139 @@
+use super::diagnostics::{Diagnostic, Severity};
+use super::errors::*;
+
+use xml::common::Position;
+use xml::reader::{self, EventReader, XmlEvent};
+
+/// Iterates over the events of a decoded document like `xml::reader::Events`
+/// and keeps the position of the last event available for error reporting,
+/// along with the location of the document when it is known.
+///
+/// A lenient reader collects the errors of malformed components as
+/// diagnostics and skips those components instead of failing.
+pub struct Reader<'a> {
+    reader: EventReader<&'a [u8]>,
+    file: Option<String>,
+    finished: bool,
+    depth: usize,
+    lenient: bool,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(decoded_contents: &'a [u8]) -> Reader<'a> {
+        Reader {
+            reader: EventReader::new(decoded_contents),
+            file: None,
+            finished: false,
+            depth: 0,
+            lenient: false,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    pub fn lenient(decoded_contents: &'a [u8]) -> Reader<'a> {
+        Reader { lenient: true, ..Reader::new(decoded_contents) }
+    }
+
+    /// Records `location` as the file of the positions reported by the reader.
+    pub fn in_file(self, location: &str) -> Reader<'a> {
+        Reader { file: Some(location.to_string()), ..self }
+    }
+
+    /// File, one-based line and column of the last event read.
+    pub fn source_position(&self) -> SourcePosition {
+        let position = self.reader.position();
+        SourcePosition {
+            file: self.file.clone(),
+            ..SourcePosition::new(position.row + 1, position.column + 1)
+        }
+    }
+
+    /// Attaches the position of the last event read to `error`, unless it
+    /// already has one.
+    pub fn locate(&self, error: Error) -> Error {
+        error.at(self.source_position())
+    }
+
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+
+    /// Records a problem that does not prevent reading the document.
+    pub fn warn(&mut self, kind: ErrorKind) {
+        let position = self.source_position();
+        self.diagnostics.push(Diagnostic::new(Severity::Warning, kind, Some(position)));
+    }
+
+    /// Records the error of a component that was left out while reading the
+    /// element at `position`. A strict reader fails with the error instead.
+    pub fn skipped(&mut self, error: Error, position: SourcePosition) -> Result<()> {
+        if !self.lenient {
+            return Err(error.at(position));
+        }
+
+        self.diagnostics.push(Diagnostic::new(Severity::Error, error.0, Some(position)));
+        Ok(())
+    }
+
+    /// Reads the component whose start element was just read. In lenient mode
+    /// a failure is recorded, the rest of the component is skipped and `None`
+    /// is returned. Malformed XML always fails.
+    pub fn component<T, F>(&mut self, read: F) -> Result<Option<T>>
+        where F: FnOnce(&mut Reader<'a>) -> Result<T>
+    {
+        let depth = self.depth;
+
+        match read(self) {
+            Ok(component) => Ok(Some(component)),
+            Err(e) => {
+                if !self.lenient || self.finished {
+                    return Err(e);
+                }
+
+                let position = self.source_position();
+                self.diagnostics.push(Diagnostic::new(Severity::Error, e.0, Some(position)));
+
+                while self.depth >= depth {
+                    match self.next() {
+                        Some(event) => {
+                            event?;
+                        }
+                        None => break,
+                    }
+                }
+
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Reader<'a> {
+    type Item = reader::Result<XmlEvent>;
+
+    fn next(&mut self) -> Option<reader::Result<XmlEvent>> {
+        if self.finished {
+            return None;
+        }
+
+        match self.reader.next() {
+            Ok(XmlEvent::EndDocument) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+            Ok(event) => {
+                match event {
+                    XmlEvent::StartElement { .. } => self.depth += 1,
+                    XmlEvent::EndElement { .. } => self.depth -= 1,
+                    _ => (),
+                }
+                Some(Ok(event))
+            }
+        }
+    }
+}
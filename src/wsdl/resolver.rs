@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use hyper::Url;
+
+use super::errors::*;
+use super::file;
+use super::http;
+
+/// Fetches WSDL and XML Schema documents. Imports are resolved against the
+/// location of the importing document before they are passed to a resolver,
+/// so locations are absolute URLs or paths.
+pub trait Resolver {
+    /// Raw contents of the document, decoding is left to the parser.
+    fn fetch(&self, location: &str) -> Result<Vec<u8>>;
+
+    /// Fetches the document unless it is unchanged since the copy the
+    /// validators were taken from. Resolvers that cannot tell always fetch.
+    fn fetch_if_modified(&self, location: &str, _validators: &Validators) -> Result<Fetched> {
+        Ok(Fetched::Modified(self.fetch(location)?, Validators::default()))
+    }
+
+    /// Location of the schema for a namespace imported without one.
+    fn locate_namespace(&self, _namespace: &str) -> Option<String> {
+        None
+    }
+
+    /// Key identifying the document, locations with the same key are loaded
+    /// only once.
+    fn normalize(&self, location: &str) -> Result<String> {
+        if is_url(location) {
+            let url = Url::parse(location)
+                .chain_err(|| ErrorKind::InvalidLocation(location.to_string()))?;
+            return Ok(url.into_string());
+        }
+
+        Ok(clean_path(location))
+    }
+}
+
+/// HTTP cache validators of a fetched document.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum Fetched {
+    Modified(Vec<u8>, Validators),
+    NotModified,
+}
+
+/// Loads paths from the file system and URLs with the blocking hyper client.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultResolver;
+
+impl Resolver for DefaultResolver {
+    fn fetch(&self, location: &str) -> Result<Vec<u8>> {
+        if is_url(location) {
+            HttpResolver.fetch(location)
+        } else {
+            FileResolver.fetch(location)
+        }
+    }
+
+    fn fetch_if_modified(&self, location: &str, validators: &Validators) -> Result<Fetched> {
+        if is_url(location) {
+            HttpResolver.fetch_if_modified(location, validators)
+        } else {
+            FileResolver.fetch_if_modified(location, validators)
+        }
+    }
+
+    fn normalize(&self, location: &str) -> Result<String> {
+        if is_url(location) {
+            HttpResolver.normalize(location)
+        } else {
+            FileResolver.normalize(location)
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FileResolver;
+
+impl Resolver for FileResolver {
+    fn fetch(&self, location: &str) -> Result<Vec<u8>> {
+        Ok(file::load(location)?)
+    }
+
+    fn normalize(&self, location: &str) -> Result<String> {
+        let path = fs::canonicalize(location)?;
+        Ok(path.to_string_lossy().into_owned())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HttpResolver;
+
+impl Resolver for HttpResolver {
+    fn fetch(&self, location: &str) -> Result<Vec<u8>> {
+        http::get(location)
+    }
+
+    fn fetch_if_modified(&self, location: &str, validators: &Validators) -> Result<Fetched> {
+        http::get_if_modified(location, validators)
+    }
+}
+
+/// Serves documents registered up front, useful to load a set of WSDL and
+/// XSD documents without touching the file system or the network.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryResolver {
+    documents: HashMap<String, Vec<u8>>,
+}
+
+impl MemoryResolver {
+    pub fn new() -> MemoryResolver {
+        MemoryResolver { documents: HashMap::new() }
+    }
+
+    pub fn insert<T: Into<Vec<u8>>>(&mut self, location: &str, contents: T) -> Result<()> {
+        let key = self.normalize(location)?;
+        self.documents.insert(key, contents.into());
+        Ok(())
+    }
+}
+
+impl Resolver for MemoryResolver {
+    fn fetch(&self, location: &str) -> Result<Vec<u8>> {
+        self.documents
+            .get(&self.normalize(location)?)
+            .cloned()
+            .ok_or_else(|| ErrorKind::InvalidLocation(location.to_string()).into())
+    }
+}
+
+pub fn is_url(location: &str) -> bool {
+    match Url::parse(location) {
+        // Single letter schemes are Windows drive letters, not URLs.
+        Ok(url) => url.scheme().len() > 1,
+        Err(_) => false,
+    }
+}
+
+/// Removes `.` and `..` components without looking at the file system.
+fn clean_path(location: &str) -> String {
+    let mut path = PathBuf::new();
+    for component in Path::new(location).components() {
+        match component {
+            Component::CurDir => continue,
+            Component::ParentDir if path.file_name().is_some() => {
+                path.pop();
+            }
+            component => path.push(component.as_os_str()),
+        }
+    }
+
+    path.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_resolver_normalizes_locations_test() {
+        let mut resolver = MemoryResolver::new();
+        resolver.insert("/wsdl/common/types.xsd", "<schema/>").unwrap();
+
+        assert_eq!(b"<schema/>".to_vec(),
+                   resolver.fetch("/wsdl/service/../common/./types.xsd").unwrap());
+        assert_eq!("http://example.com/a.wsdl",
+                   resolver.normalize("http://EXAMPLE.com/wsdl/../a.wsdl").unwrap());
+    }
+}
@@ -1,18 +1,27 @@
 use super::errors::*;
 
-use super::http;
-use super::file;
+use std::collections::BTreeMap;
+
+use super::bundle::Bundle;
+use super::charset;
+use super::diagnostics::Diagnostic;
+use super::element::{declared_namespaces, XmlElement};
+use super::import::{is_xsd, Importer};
+use super::reader::Reader;
+use super::resolver::{DefaultResolver, Resolver};
+use super::schema_set::SchemaSet;
+use super::soap::*;
+use super::wsdl2;
+use super::xsd::{XsdComplexType, XsdElement, XsdSchema, XsdSimpleType};
 
 use xml::attribute::OwnedAttribute;
+use xml::escape::{escape_str_attribute, escape_str_pcdata};
 use xml::name::OwnedName;
-use xml::namespace::Namespace;
-use xml::reader::{EventReader, Events, XmlEvent};
+use xml::namespace::{Namespace, NS_NO_PREFIX};
+use xml::reader::XmlEvent;
 
-use encoding::all::UTF_8;
-use encoding::DecoderTrap;
-use encoding::types::decode;
-
-const NS_WSDL: &'static str = "http://schemas.xmlsoap.org/wsdl/";
+pub const NS_WSDL: &str = "http://schemas.xmlsoap.org/wsdl/";
+pub const NS_WSDL_HTTP: &str = "http://schemas.xmlsoap.org/wsdl/http/";
 
 pub trait Documented {
     fn get_documentation(&self) -> &Option<WsdlDocumentation>;
@@ -42,24 +51,57 @@ macro_rules! impl_named_item {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WsdlVersion {
+    Wsdl11,
+    Wsdl20,
+}
+
 #[derive(Debug)]
 pub struct Wsdl {
+    pub version: WsdlVersion,
     pub documentation: Option<WsdlDocumentation>,
+    pub name: Option<String>,
     pub target_namespace: Option<String>,
+    /// Namespace declarations of the root element, by prefix.
+    pub namespaces: BTreeMap<String, String>,
+    pub imports: Vec<WsdlImport>,
     pub types: Vec<WsdlTypes>,
     pub port_types: Vec<WsdlPortType>,
     pub services: Vec<WsdlService>,
     pub bindings: Vec<WsdlBinding>,
     pub messages: Vec<WsdlMessage>,
+    pub extensions: Vec<WsdlExtension>,
+    pub extension_attributes: Vec<OwnedAttribute>,
 }
 
 impl_documented!(Wsdl);
 
+#[derive(Debug)]
+pub struct WsdlImport {
+    pub documentation: Option<WsdlDocumentation>,
+    pub namespace: String,
+    pub location: String,
+    /// Location of the imported document the component was merged from,
+    /// see `Wsdl::merge_imported`.
+    pub imported_from: Option<String>,
+    pub extensions: Vec<WsdlExtension>,
+    pub extension_attributes: Vec<OwnedAttribute>,
+}
+
+impl_documented!(WsdlImport);
+
 #[derive(Debug)]
 pub struct WsdlService {
     pub documentation: Option<WsdlDocumentation>,
+    pub target_namespace: Option<String>,
+    /// Location of the imported document the component was merged from,
+    /// see `Wsdl::merge_imported`.
+    pub imported_from: Option<String>,
     pub name: String,
     pub ports: Vec<WsdlPort>,
+    pub extensions: Vec<WsdlExtension>,
+    pub extension_attributes: Vec<OwnedAttribute>,
 }
 
 impl_documented!(WsdlService);
@@ -68,8 +110,16 @@ impl_named_item!(WsdlService);
 #[derive(Debug)]
 pub struct WsdlBinding {
     pub documentation: Option<WsdlDocumentation>,
+    pub target_namespace: Option<String>,
+    /// Location of the imported document the component was merged from,
+    /// see `Wsdl::merge_imported`.
+    pub imported_from: Option<String>,
     pub name: String,
     pub port_type: OwnedName,
+    pub soap_binding: Option<SoapBinding>,
+    pub http_binding: Option<HttpBinding>,
+    pub extensions: Vec<WsdlExtension>,
+    pub extension_attributes: Vec<OwnedAttribute>,
     pub operations: Vec<WsdlOperationBinding>,
 }
 
@@ -79,8 +129,14 @@ impl_named_item!(WsdlBinding);
 #[derive(Debug)]
 pub struct WsdlMessage {
     pub documentation: Option<WsdlDocumentation>,
+    pub target_namespace: Option<String>,
+    /// Location of the imported document the component was merged from,
+    /// see `Wsdl::merge_imported`.
+    pub imported_from: Option<String>,
     pub name: String,
     pub parts: Vec<WsdlMessagePart>,
+    pub extensions: Vec<WsdlExtension>,
+    pub extension_attributes: Vec<OwnedAttribute>,
 }
 
 impl_documented!(WsdlMessage);
@@ -91,18 +147,52 @@ pub struct WsdlPort {
     pub documentation: Option<WsdlDocumentation>,
     pub name: String,
     pub binding: OwnedName,
+    pub address: Option<WsdlAddress>,
+    pub extensions: Vec<WsdlExtension>,
+    pub extension_attributes: Vec<OwnedAttribute>,
 }
 
 impl_documented!(WsdlPort);
 impl_named_item!(WsdlPort);
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressProtocol {
+    Soap11,
+    Soap12,
+    Http,
+}
+
+/// `http:binding` of WSDL 1.1, or the HTTP binding type of WSDL 2.0.
+#[derive(Debug)]
+pub struct HttpBinding {
+    pub method: Option<String>,
+}
+
+/// `http:operation` of WSDL 1.1, or `whttp` attributes of a WSDL 2.0 binding operation.
+#[derive(Debug)]
+pub struct HttpOperation {
+    pub method: Option<String>,
+    pub location: Option<String>,
+}
+
+/// Endpoint of a port, from its `soap:address`, `soap12:address` or `http:address`.
+#[derive(Debug)]
+pub struct WsdlAddress {
+    pub protocol: AddressProtocol,
+    pub location: String,
+}
+
 #[derive(Debug)]
 pub struct WsdlOperationBinding {
     pub documentation: Option<WsdlDocumentation>,
     pub name: String,
+    pub soap_operation: Option<SoapOperation>,
+    pub http_operation: Option<HttpOperation>,
+    pub extensions: Vec<WsdlExtension>,
+    pub extension_attributes: Vec<OwnedAttribute>,
     pub input: Option<WsdlInputBinding>,
     pub output: Option<WsdlOutputBinding>,
-    pub fault: Option<WsdlFaultBinding>,
+    pub faults: Vec<WsdlFaultBinding>,
 }
 
 impl_documented!(WsdlOperationBinding);
@@ -110,15 +200,25 @@ impl_named_item!(WsdlOperationBinding);
 
 #[derive(Debug)]
 pub struct WsdlMessagePart {
+    pub documentation: Option<WsdlDocumentation>,
     pub name: String,
     pub element: Option<OwnedName>,
     pub part_type: Option<OwnedName>,
+    pub extensions: Vec<WsdlExtension>,
+    pub extension_attributes: Vec<OwnedAttribute>,
 }
 
+impl_documented!(WsdlMessagePart);
+impl_named_item!(WsdlMessagePart);
+
 #[derive(Debug)]
 pub struct WsdlInputBinding {
     pub documentation: Option<WsdlDocumentation>,
-    pub text: String,
+    pub name: Option<String>,
+    pub soap_body: Option<SoapBody>,
+    pub soap_headers: Vec<SoapHeader>,
+    pub extensions: Vec<WsdlExtension>,
+    pub extension_attributes: Vec<OwnedAttribute>,
 }
 
 impl_documented!(WsdlInputBinding);
@@ -126,6 +226,11 @@ impl_documented!(WsdlInputBinding);
 #[derive(Debug)]
 pub struct WsdlOutputBinding {
     pub documentation: Option<WsdlDocumentation>,
+    pub name: Option<String>,
+    pub soap_body: Option<SoapBody>,
+    pub soap_headers: Vec<SoapHeader>,
+    pub extensions: Vec<WsdlExtension>,
+    pub extension_attributes: Vec<OwnedAttribute>,
 }
 
 impl_documented!(WsdlOutputBinding);
@@ -134,14 +239,31 @@ impl_documented!(WsdlOutputBinding);
 pub struct WsdlFaultBinding {
     pub documentation: Option<WsdlDocumentation>,
     pub name: String,
+    pub soap_fault: Option<SoapFault>,
+    pub extensions: Vec<WsdlExtension>,
+    pub extension_attributes: Vec<OwnedAttribute>,
 }
 
 impl_documented!(WsdlFaultBinding);
 impl_named_item!(WsdlFaultBinding);
 
+/// Extensibility element from a namespace other than WSDL that is not
+/// interpreted, e.g. `xrd:version` or `wsp:Policy`.
+pub type WsdlExtension = XmlElement;
+
 #[derive(Debug)]
 pub struct WsdlTypes {
     pub documentation: Option<WsdlDocumentation>,
+    pub target_namespace: Option<String>,
+    /// Location of the imported document the component was merged from,
+    /// see `Wsdl::merge_imported`.
+    pub imported_from: Option<String>,
+    /// Schema elements of the section, kept as they were read.
+    pub schemas: Vec<XmlElement>,
+    /// Components of each of the `xs:schema` elements in `schemas`. The ones
+    /// that cannot be read fail `Wsdl::parse`, `Wsdl::parse_lenient` leaves
+    /// them out and reports them.
+    pub models: Vec<XsdSchema>,
 }
 
 impl_documented!(WsdlTypes);
@@ -149,43 +271,147 @@ impl_documented!(WsdlTypes);
 #[derive(Debug)]
 pub struct WsdlPortType {
     pub documentation: Option<WsdlDocumentation>,
+    pub target_namespace: Option<String>,
+    /// Location of the imported document the component was merged from,
+    /// see `Wsdl::merge_imported`.
+    pub imported_from: Option<String>,
     pub name: String,
+    pub extends: Vec<OwnedName>,
+    pub operations: Vec<WsdlOperation>,
+    pub extensions: Vec<WsdlExtension>,
+    pub extension_attributes: Vec<OwnedAttribute>,
 }
 
 impl_documented!(WsdlPortType);
 impl_named_item!(WsdlPortType);
 
+#[derive(Debug)]
+pub struct WsdlOperation {
+    pub documentation: Option<WsdlDocumentation>,
+    pub name: String,
+    pub pattern: Option<String>,
+    pub parameter_order: Vec<String>,
+    pub input: Option<WsdlOperationMessage>,
+    pub output: Option<WsdlOperationMessage>,
+    pub faults: Vec<WsdlOperationMessage>,
+    pub extensions: Vec<WsdlExtension>,
+    pub extension_attributes: Vec<OwnedAttribute>,
+}
+
+impl_documented!(WsdlOperation);
+impl_named_item!(WsdlOperation);
+
+/// Input, output or fault of an abstract operation. WSDL 1.1 refers to a
+/// `message`, WSDL 2.0 directly to a schema `element`.
+#[derive(Debug)]
+pub struct WsdlOperationMessage {
+    pub documentation: Option<WsdlDocumentation>,
+    pub name: Option<String>,
+    pub message: Option<OwnedName>,
+    pub element: Option<OwnedName>,
+    pub extensions: Vec<WsdlExtension>,
+    pub extension_attributes: Vec<OwnedAttribute>,
+}
+
+impl_documented!(WsdlOperationMessage);
+
 #[derive(Debug)]
 pub struct WsdlDocumentation {
-    pub text: String
+    pub text: String,
+    /// Inner XML when the documentation contains child elements.
+    pub markup: Option<String>,
 }
 
 impl Wsdl {
+    /// Loads a document and the WSDL documents it imports. The XML Schema
+    /// documents its schemas import or include are only loaded by
+    /// `load_with_schemas`.
     pub fn load_from_url(url: &str) -> Result<Wsdl> {
-        let contents = http::get(url)?;
-        let decoded_contents = decode_contents(&contents)?;
-        Wsdl::parse(&decoded_contents[..])
+        Importer::new(&DefaultResolver).load(url)
     }
 
+    /// Loads a document and the WSDL documents it imports like `load_from_url`.
     pub fn load_from_file(location: &str) -> Result<Wsdl> {
-        let contents = file::load(location)?;
-        let decoded_contents = decode_contents(&contents)?;
-        Wsdl::parse(&decoded_contents[..])
+        Importer::new(&DefaultResolver).load(location)
+    }
+
+    /// Loads the entry point of a zip archive of WSDL and XSD documents,
+    /// see `Bundle::load`.
+    pub fn load_from_zip(archive: &str, entry: &str) -> Result<Wsdl> {
+        Bundle::from_zip(archive)?.load(entry)
+    }
+
+    pub fn load_from_directory(directory: &str, entry: &str) -> Result<Wsdl> {
+        Bundle::from_directory(directory)?.load(entry)
+    }
+
+    /// Loads a document and its imports through `resolver`.
+    pub fn load_with_resolver<R: Resolver>(location: &str, resolver: &R) -> Result<Wsdl> {
+        Importer::new(resolver).load(location)
+    }
+
+    /// Loads a document and its imports through `resolver`, along with the
+    /// XML Schema documents its schemas import or include, see
+    /// `Importer::resolve_schemas`.
+    pub fn load_with_schemas<R: Resolver>(location: &str, resolver: &R) -> Result<Wsdl> {
+        Importer::new(resolver).resolve_schemas().load(location)
     }
 
+    /// Parses a decoded document. Errors raised while reading it carry the
+    /// position of the offending element, see `Error::position`.
     pub fn parse(decoded_contents: &[u8]) -> Result<Wsdl> {
+        Wsdl::parse_with(Reader::new(decoded_contents))
+    }
+
+    /// Parses a decoded document read from `location`, which is the file of
+    /// the positions of its errors.
+    pub fn parse_in_file(decoded_contents: &[u8], location: &str) -> Result<Wsdl> {
+        Wsdl::parse_with(Reader::new(decoded_contents).in_file(location))
+    }
+
+    /// Parses a decoded document, skipping the components that cannot be read.
+    /// Their errors are returned along with warnings about unexpected content.
+    /// Only malformed XML or a missing root element fail the parse.
+    pub fn parse_lenient(decoded_contents: &[u8]) -> Result<(Wsdl, Vec<Diagnostic>)> {
+        Wsdl::parse_lenient_with(Reader::lenient(decoded_contents))
+    }
+
+    /// Parses a decoded document read from `location` like `parse_lenient`,
+    /// `location` is the file of the positions of errors and diagnostics.
+    pub fn parse_lenient_in_file(decoded_contents: &[u8],
+                                 location: &str)
+                                 -> Result<(Wsdl, Vec<Diagnostic>)> {
+        Wsdl::parse_lenient_with(Reader::lenient(decoded_contents).in_file(location))
+    }
+
+    fn parse_with(mut iter: Reader) -> Result<Wsdl> {
+        Wsdl::read_document(&mut iter).map_err(|e| iter.locate(e))
+    }
+
+    fn parse_lenient_with(mut iter: Reader) -> Result<(Wsdl, Vec<Diagnostic>)> {
+        let wsdl = Wsdl::read_document(&mut iter).map_err(|e| iter.locate(e))?;
+
+        Ok((wsdl, iter.into_diagnostics()))
+    }
+
+    fn read_document(iter: &mut Reader) -> Result<Wsdl> {
         let ns_wsdl = Some(NS_WSDL.to_string());
-        let parser = EventReader::new(decoded_contents);
-        let mut iter = parser.into_iter();
 
         while let Some(v) = iter.next() {
             match v? {
                 XmlEvent::StartElement {
                     ref name,
                     ref attributes,
-                    ..
+                    ref namespace,
                 } if name.namespace == ns_wsdl && name.local_name == "definitions" => {
-                    return Wsdl::read(attributes, &mut iter);
+                    return Wsdl::read(attributes, namespace, iter);
+                }
+                XmlEvent::StartElement {
+                    ref name,
+                    ref attributes,
+                    ref namespace,
+                } if wsdl2::is_description(name) => {
+                    return wsdl2::read_description(attributes, namespace, iter);
                 }
                 _ => continue,
             }
@@ -194,17 +420,22 @@ impl Wsdl {
         Err(ErrorKind::MissingElement("definitions".to_string()).into())
     }
 
-    fn read(attributes: &[OwnedAttribute], mut iter: &mut Events<&[u8]>) -> Result<Wsdl> {
+    fn read(attributes: &[OwnedAttribute],
+            namespace: &Namespace,
+            mut iter: &mut Reader)
+            -> Result<Wsdl> {
         let ns_wsdl = Some(NS_WSDL.to_string());
         let target_namespace = find_attribute("targetNamespace", attributes);
 
         let mut depth = 0;
         let mut documentation = None;
+        let mut imports = Vec::new();
         let mut types = Vec::new();
         let mut port_types = Vec::new();
         let mut services = Vec::new();
         let mut bindings = Vec::new();
         let mut messages = Vec::new();
+        let mut extensions = Vec::new();
 
         while let Some(v) = iter.next() {
             match (v?, depth) {
@@ -213,8 +444,14 @@ impl Wsdl {
                                                                 "documentation" => {
                     documentation = Some(WsdlDocumentation::read(&mut iter)?)
                 }
-                (XmlEvent::StartElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
-                                                                name.local_name == "import" => unimplemented!(),
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ..
+                 },
+                 0) if name.namespace == ns_wsdl && name.local_name == "import" => {
+                    imports.extend(iter.component(|iter| WsdlImport::read(attributes, iter))?)
+                }
                 (XmlEvent::StartElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
                                                                 name.local_name == "types" => {
                     types.push(WsdlTypes::read(&mut iter)?)
@@ -225,7 +462,7 @@ impl Wsdl {
                      ..
                  },
                  0) if name.namespace == ns_wsdl && name.local_name == "message" => {
-                    messages.push(WsdlMessage::read(attributes, &mut iter)?)
+                    messages.extend(iter.component(|iter| WsdlMessage::read(attributes, iter))?)
                 }
                 (XmlEvent::StartElement {
                      ref name,
@@ -233,7 +470,7 @@ impl Wsdl {
                      ..
                  },
                  0) if name.namespace == ns_wsdl && name.local_name == "portType" => {
-                    port_types.push(WsdlPortType::read(attributes, &mut iter)?)
+                    port_types.extend(iter.component(|iter| WsdlPortType::read(attributes, iter))?)
                 }
                 (XmlEvent::StartElement {
                      ref name,
@@ -241,7 +478,7 @@ impl Wsdl {
                      ref namespace,
                  },
                  0) if name.namespace == ns_wsdl && name.local_name == "binding" => {
-                    bindings.push(WsdlBinding::read(attributes, namespace, &mut iter)?)
+                    bindings.extend(iter.component(|iter| WsdlBinding::read(attributes, namespace, iter))?)
                 }
                 (XmlEvent::StartElement {
                      ref name,
@@ -249,7 +486,19 @@ impl Wsdl {
                      ..
                  },
                  0) if name.namespace == ns_wsdl && name.local_name == "service" => {
-                    services.push(WsdlService::read(attributes, &mut iter)?)
+                    services.extend(iter.component(|iter| WsdlService::read(attributes, iter))?)
+                }
+                (XmlEvent::StartElement { ref name, .. }, 0) if name.namespace == ns_wsdl => {
+                    iter.warn(ErrorKind::InvalidElement(format!("wsdl:{}", name.local_name)));
+                    skip_element(iter)?;
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if name.namespace != ns_wsdl => {
+                    extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
                 }
                 (XmlEvent::StartElement { .. }, _) => depth += 1,
                 (XmlEvent::EndElement { ref name }, 0) if name.namespace == ns_wsdl &&
@@ -259,35 +508,182 @@ impl Wsdl {
             }
         }
 
-        Ok(Wsdl {
+        let mut wsdl = Wsdl {
+            version: WsdlVersion::Wsdl11,
+            documentation,
+            name: find_attribute("name", attributes),
+            target_namespace,
+            namespaces: declared_namespaces(namespace, None),
+            imports,
+            types,
+            port_types,
+            services,
+            bindings,
+            messages,
+            extensions,
+            extension_attributes: extension_attributes(attributes),
+        };
+        wsdl.assign_target_namespace();
+
+        Ok(wsdl)
+    }
+
+    /// Qualifies top-level components with the target namespace of the document.
+    pub(crate) fn assign_target_namespace(&mut self) {
+        for types in &mut self.types {
+            types.target_namespace = self.target_namespace.clone();
+        }
+        for message in &mut self.messages {
+            message.target_namespace = self.target_namespace.clone();
+        }
+        for port_type in &mut self.port_types {
+            port_type.target_namespace = self.target_namespace.clone();
+        }
+        for binding in &mut self.bindings {
+            binding.target_namespace = self.target_namespace.clone();
+        }
+        for service in &mut self.services {
+            service.target_namespace = self.target_namespace.clone();
+        }
+    }
+
+    /// Appends the components of the definitions imported from `location`
+    /// like `merge`, and records `location` in the `imported_from` field of
+    /// the components that were not already imported by `other`.
+    pub fn merge_imported(&mut self, mut other: Wsdl, location: &str) {
+        let mark = |imported_from: &mut Option<String>| if imported_from.is_none() {
+            *imported_from = Some(location.to_string());
+        };
+
+        for import in &mut other.imports {
+            mark(&mut import.imported_from);
+        }
+        for types in &mut other.types {
+            mark(&mut types.imported_from);
+        }
+        for port_type in &mut other.port_types {
+            mark(&mut port_type.imported_from);
+        }
+        for service in &mut other.services {
+            mark(&mut service.imported_from);
+        }
+        for binding in &mut other.bindings {
+            mark(&mut binding.imported_from);
+        }
+        for message in &mut other.messages {
+            mark(&mut message.imported_from);
+        }
+
+        self.merge(other);
+    }
+
+    /// Appends the components of an imported definition. The components keep
+    /// the target namespace of the document they were defined in.
+    pub fn merge(&mut self, other: Wsdl) {
+        self.imports.extend(other.imports);
+        self.types.extend(other.types);
+        self.port_types.extend(other.port_types);
+        self.services.extend(other.services);
+        self.bindings.extend(other.bindings);
+        self.messages.extend(other.messages);
+    }
+
+    pub fn find_message(&self, name: &OwnedName) -> Option<&WsdlMessage> {
+        self.messages.iter().find(|m| is_named(name, &m.target_namespace, &m.name))
+    }
+
+    pub fn find_port_type(&self, name: &OwnedName) -> Option<&WsdlPortType> {
+        self.port_types.iter().find(|p| is_named(name, &p.target_namespace, &p.name))
+    }
+
+    pub fn find_binding(&self, name: &OwnedName) -> Option<&WsdlBinding> {
+        self.bindings.iter().find(|b| is_named(name, &b.target_namespace, &b.name))
+    }
+
+    /// Schemas of every types section, the ones loaded for them included.
+    pub fn schemas(&self) -> Vec<&XsdSchema> {
+        self.types.iter().flat_map(|t| &t.models).collect()
+    }
+
+    pub fn schema_set(&self) -> SchemaSet<'_> {
+        SchemaSet::new(self.schemas())
+    }
+
+    pub fn find_schema_element(&self, name: &OwnedName) -> Option<&XsdElement> {
+        self.schema_set().find_element(name)
+    }
+
+    pub fn find_complex_type(&self, name: &OwnedName) -> Option<&XsdComplexType> {
+        self.schema_set().find_complex_type(name)
+    }
+
+    pub fn find_simple_type(&self, name: &OwnedName) -> Option<&XsdSimpleType> {
+        self.schema_set().find_simple_type(name)
+    }
+}
+
+impl WsdlImport {
+    fn read(attributes: &[OwnedAttribute], iter: &mut Reader) -> Result<WsdlImport> {
+        let namespace = find_attribute("namespace", attributes)
+            .ok_or_else(|| {
+                            ErrorKind::MandatoryAttribute("namespace".to_string(),
+                                                          "wsdl:import".to_string())
+                        })?;
+        let location = find_attribute("location", attributes)
+            .ok_or_else(|| {
+                            ErrorKind::MandatoryAttribute("location".to_string(),
+                                                          "wsdl:import".to_string())
+                        })?;
+
+        let (documentation, extensions) = read_documented("import", iter)?;
+
+        Ok(WsdlImport {
                documentation,
-               target_namespace,
-               types,
-               port_types,
-               services,
-               bindings,
-               messages,
+               namespace,
+               location,
+               imported_from: None,
+               extensions,
+               extension_attributes: extension_attributes(attributes),
            })
     }
 }
 
 impl WsdlService {
-    fn read(attributes: &[OwnedAttribute], iter: &mut Events<&[u8]>) -> Result<WsdlService> {
+    fn read(attributes: &[OwnedAttribute], iter: &mut Reader) -> Result<WsdlService> {
         let ns_wsdl = Some(NS_WSDL.to_string());
-        let service_name = find_attribute("name", attributes);
+        let service_name = find_attribute("name", attributes)
+            .ok_or_else(|| {
+                            ErrorKind::MandatoryAttribute("name".to_string(),
+                                                          "wsdl:service".to_string())
+                        })?;
 
         let mut depth = 0;
+        let mut documentation = None;
         let mut ports = Vec::new();
+        let mut extensions = Vec::new();
 
-        for event in iter {
-            match (event?, depth) {
+        while let Some(v) = iter.next() {
+            match (v?, depth) {
+                (XmlEvent::StartElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                                name.local_name ==
+                                                                "documentation" => {
+                    documentation = Some(WsdlDocumentation::read(iter)?)
+                }
                 (XmlEvent::StartElement {
                      ref name,
                      ref attributes,
                      ref namespace,
                  },
                  0) if name.namespace == ns_wsdl && name.local_name == "port" => {
-                    ports.push(WsdlPort::read(attributes, namespace)?)
+                    ports.extend(iter.component(|iter| WsdlPort::read(attributes, namespace, iter))?)
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if name.namespace != ns_wsdl => {
+                    extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
                 }
                 (XmlEvent::StartElement { .. }, _) => depth += 1,
                 (XmlEvent::EndElement { ref name }, 0) if name.namespace == ns_wsdl &&
@@ -298,13 +694,13 @@ impl WsdlService {
         }
 
         Ok(WsdlService {
-               documentation: None,
-               name: service_name
-                   .ok_or_else(|| {
-                                   ErrorKind::MandatoryAttribute("name".to_string(),
-                                                                 "wsdl:service".to_string())
-                               })?,
+               documentation,
+               target_namespace: None,
+               imported_from: None,
+               name: service_name,
                ports,
+               extensions,
+               extension_attributes: extension_attributes(attributes),
            })
     }
 }
@@ -312,9 +708,10 @@ impl WsdlService {
 impl WsdlBinding {
     fn read(attributes: &[OwnedAttribute],
             namespace: &Namespace,
-            iter: &mut Events<&[u8]>)
+            iter: &mut Reader)
             -> Result<WsdlBinding> {
         let ns_wsdl = Some(NS_WSDL.to_string());
+        let ns_http = Some(NS_WSDL_HTTP.to_string());
         let mut binding_name = None;
         let mut port_type = None;
 
@@ -328,38 +725,83 @@ impl WsdlBinding {
             }
         }
 
-        let mut port_type: OwnedName = port_type
+        let binding_name = binding_name
+            .ok_or_else(|| {
+                            ErrorKind::MandatoryAttribute("name".to_string(),
+                                                          "wsdl:binding".to_string())
+                        })?;
+        let port_type = port_type
             .ok_or_else(|| {
                             ErrorKind::MandatoryAttribute("type".to_string(),
                                                           "wsdl:binding".to_string())
-                        })?
-            .parse()
-            .unwrap();
-
-        if let Some(ref pfx) = port_type.prefix {
-            port_type.namespace = namespace.get(pfx).map(|x| x.to_string());
-        }
+                        })?;
+        let port_type = read_qname(&port_type, namespace)?;
 
+        let mut depth = 0;
+        let mut documentation = None;
+        let mut soap_binding = None;
+        let mut http_binding = None;
+        let mut extensions = Vec::new();
         let mut operations = Vec::new();
 
-        for event in iter {
-            match event? {
-                XmlEvent::StartElement {
-                    ref name,
-                    ref attributes,
-                    ..
-                } if name.namespace == ns_wsdl && name.local_name == "operation" => {
-                    operations.push(WsdlOperationBinding::read(attributes)?);
+        while let Some(v) = iter.next() {
+            match (v?, depth) {
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ..
+                 },
+                 0) if name.namespace == ns_wsdl && name.local_name == "operation" => {
+                    operations.extend(iter.component(|iter| WsdlOperationBinding::read(attributes, iter))?);
+                }
+                (XmlEvent::StartElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                                name.local_name ==
+                                                                "documentation" => {
+                    documentation = Some(WsdlDocumentation::read(iter)?)
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ..
+                 },
+                 0) if is_soap_element(name, "binding") => {
+                    soap_binding = Some(SoapBinding::read(name, attributes)?);
+                    skip_element(iter)?;
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ..
+                 },
+                 0) if name.namespace == ns_http && name.local_name == "binding" => {
+                    http_binding = Some(HttpBinding { method: find_attribute("verb", attributes) });
+                    skip_element(iter)?;
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if name.namespace != ns_wsdl => {
+                    extensions.push(XmlElement::read(name, attributes, namespace, iter)?);
                 }
-                XmlEvent::EndElement { ref name, .. } if name.namespace == ns_wsdl &&
-                                                         name.local_name == "binding" => {;
+                (XmlEvent::StartElement { .. }, _) => depth += 1,
+                (XmlEvent::EndElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                              name.local_name == "binding" => {
                     return Ok(WsdlBinding {
-                            documentation: None,
-                            name: binding_name.ok_or_else(|| ErrorKind::MandatoryAttribute("name".to_string(), "wsdl:binding".to_string()))?,
+                            documentation,
+                            target_namespace: None,
+                            imported_from: None,
+                            name: binding_name,
                             port_type,
+                            soap_binding,
+                            http_binding,
+                            extensions,
+                            extension_attributes: extension_attributes(attributes),
                             operations
                         });
                 }
+                (XmlEvent::EndElement { .. }, _) => depth -= 1,
                 _ => continue,
             }
         }
@@ -368,30 +810,103 @@ impl WsdlBinding {
     }
 }
 
+impl WsdlService {
+    pub fn find_port(&self, name: &str) -> Option<&WsdlPort> {
+        self.ports.iter().find(|p| p.name == name)
+    }
+
+    /// Endpoint URL of the named port.
+    pub fn endpoint(&self, port_name: &str) -> Option<&str> {
+        self.find_port(port_name)
+            .and_then(|p| p.address.as_ref())
+            .map(|a| a.location.as_str())
+    }
+}
+
+impl WsdlBinding {
+    /// SOAP protocol version of the binding, `None` for non-SOAP bindings.
+    pub fn soap_version(&self) -> Option<SoapVersion> {
+        self.soap_binding.as_ref().map(|b| b.version)
+    }
+
+    /// Default style of the operations, `document` when not specified.
+    pub fn style(&self) -> SoapStyle {
+        self.soap_binding
+            .as_ref()
+            .and_then(|b| b.style)
+            .unwrap_or(SoapStyle::Document)
+    }
+
+    /// Style of an operation of this binding, falling back to the binding style.
+    pub fn operation_style(&self, operation: &WsdlOperationBinding) -> SoapStyle {
+        operation
+            .soap_operation
+            .as_ref()
+            .and_then(|o| o.style)
+            .unwrap_or_else(|| self.style())
+    }
+}
+
+impl WsdlOperationBinding {
+    pub fn soap_action(&self) -> Option<&str> {
+        self.soap_operation
+            .as_ref()
+            .and_then(|o| o.soap_action.as_ref())
+            .map(|a| a.as_str())
+    }
+}
+
 impl WsdlMessage {
-    fn read(attributes: &[OwnedAttribute], iter: &mut Events<&[u8]>) -> Result<WsdlMessage> {
+    fn read(attributes: &[OwnedAttribute], iter: &mut Reader) -> Result<WsdlMessage> {
         let ns_wsdl = Some(NS_WSDL.to_string());
-        let message_name = find_attribute("name", attributes);
+        let message_name = find_attribute("name", attributes)
+            .ok_or_else(|| {
+                            ErrorKind::MandatoryAttribute("name".to_string(),
+                                                          "wsdl:message".to_string())
+                        })?;
 
+        let mut depth = 0;
+        let mut documentation = None;
         let mut parts = Vec::new();
+        let mut extensions = Vec::new();
 
-        for event in iter {
-            match event? {
-                XmlEvent::StartElement {
-                    ref name,
-                    ref attributes,
-                    ..
-                } if name.namespace == ns_wsdl && name.local_name == "part" => {
-                    parts.push(WsdlMessagePart::read(attributes)?);
+        while let Some(v) = iter.next() {
+            match (v?, depth) {
+                (XmlEvent::StartElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                                name.local_name ==
+                                                                "documentation" => {
+                    documentation = Some(WsdlDocumentation::read(iter)?)
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if name.namespace == ns_wsdl && name.local_name == "part" => {
+                    parts.extend(iter.component(|iter| WsdlMessagePart::read(attributes, namespace, iter))?);
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if name.namespace != ns_wsdl => {
+                    extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
                 }
-                XmlEvent::EndElement { ref name, .. } if name.namespace == ns_wsdl &&
-                                                         name.local_name == "message" => {
+                (XmlEvent::StartElement { .. }, _) => depth += 1,
+                (XmlEvent::EndElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                              name.local_name == "message" => {
                     return Ok(WsdlMessage {
-                            documentation: None,
-                            name: message_name.ok_or_else(|| ErrorKind::MandatoryAttribute("name".to_string(), "wsdl:message".to_string()))?,
-                            parts
+                            documentation,
+                            target_namespace: None,
+                            imported_from: None,
+                            name: message_name,
+                            parts,
+                            extensions,
+                            extension_attributes: extension_attributes(attributes),
                         });
                 }
+                (XmlEvent::EndElement { .. }, _) => depth -= 1,
                 _ => continue,
             }
         }
@@ -401,7 +916,11 @@ impl WsdlMessage {
 }
 
 impl WsdlPort {
-    fn read(attributes: &[OwnedAttribute], namespace: &Namespace) -> Result<WsdlPort> {
+    fn read(attributes: &[OwnedAttribute],
+            namespace: &Namespace,
+            iter: &mut Reader)
+            -> Result<WsdlPort> {
+        let ns_wsdl = Some(NS_WSDL.to_string());
         let mut name = None;
         let mut binding = None;
 
@@ -415,91 +934,428 @@ impl WsdlPort {
             }
         }
 
-        let mut binding: OwnedName = binding
+        let binding = binding
             .ok_or_else(|| {
                             ErrorKind::MandatoryAttribute("binding".to_string(),
                                                           "wsdl:port".to_string())
-                        })?
-            .parse()
-            .unwrap();
+                        })?;
+        let binding = read_qname(&binding, namespace)?;
 
-        if let Some(ref pfx) = binding.prefix {
-            binding.namespace = namespace.get(pfx).map(|x| x.to_string());
-        }
+        let mut depth = 0;
+        let mut documentation = None;
+        let mut address = None;
+        let mut extensions = Vec::new();
 
-        Ok(WsdlPort {
-               documentation: None,
-               name: name.ok_or_else(|| {
-                                         ErrorKind::MandatoryAttribute("name".to_string(),
-                                                                       "wsdl:port".to_string())
-                                     })?,
+        while let Some(v) = iter.next() {
+            match (v?, depth) {
+                (XmlEvent::StartElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                                name.local_name ==
+                                                                "documentation" => {
+                    documentation = Some(WsdlDocumentation::read(iter)?)
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ..
+                 },
+                 0) if name.local_name == "address" && is_address(name) => {
+                    address = WsdlAddress::read(name, attributes)?;
+                    skip_element(iter)?;
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if name.namespace != ns_wsdl => {
+                    extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
+                }
+                (XmlEvent::StartElement { .. }, _) => depth += 1,
+                (XmlEvent::EndElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                              name.local_name == "port" => {
+                    break;
+                }
+                (XmlEvent::EndElement { .. }, _) => depth -= 1,
+                _ => continue,
+            }
+        }
+
+        Ok(WsdlPort {
+               documentation,
+               name: name.ok_or_else(|| {
+                                         ErrorKind::MandatoryAttribute("name".to_string(),
+                                                                       "wsdl:port".to_string())
+                                     })?,
                binding,
+               address,
+               extensions,
+               extension_attributes: extension_attributes(attributes),
            })
     }
 }
 
+impl WsdlAddress {
+    /// Reads an address extension element, returns `None` for unknown protocols.
+    fn read(name: &OwnedName, attributes: &[OwnedAttribute]) -> Result<Option<WsdlAddress>> {
+        let protocol = match address_protocol(name) {
+            Some(protocol) => protocol,
+            None => return Ok(None),
+        };
+
+        let location = find_attribute("location", attributes)
+            .ok_or_else(|| {
+                            ErrorKind::MandatoryAttribute("location".to_string(),
+                                                          format!("{}:address",
+                                                                  name.prefix
+                                                                      .clone()
+                                                                      .unwrap_or_default()))
+                        })?;
+
+        Ok(Some(WsdlAddress { protocol, location }))
+    }
+}
+
+fn address_protocol(name: &OwnedName) -> Option<AddressProtocol> {
+    match soap_version(name) {
+        Some(SoapVersion::Soap11) => Some(AddressProtocol::Soap11),
+        Some(SoapVersion::Soap12) => Some(AddressProtocol::Soap12),
+        None if name.namespace.as_deref() == Some(NS_WSDL_HTTP) => {
+            Some(AddressProtocol::Http)
+        }
+        None => None,
+    }
+}
+
+fn is_address(name: &OwnedName) -> bool {
+    address_protocol(name).is_some()
+}
+
 impl WsdlOperationBinding {
-    fn read(attributes: &[OwnedAttribute]) -> Result<WsdlOperationBinding> {
-        let name = find_attribute("name", attributes);
-        let internals = attributes
-            .iter()
-            .map(|attr| attr.value.clone())
-            .collect::<Vec<_>>()
-            .join("");
+    fn read(attributes: &[OwnedAttribute],
+            iter: &mut Reader)
+            -> Result<WsdlOperationBinding> {
+        let ns_wsdl = Some(NS_WSDL.to_string());
+        let ns_http = Some(NS_WSDL_HTTP.to_string());
+        let name = find_attribute("name", attributes)
+            .ok_or_else(|| {
+                            ErrorKind::MandatoryAttribute("name".to_string(),
+                                                          "wsdl:operation".to_string())
+                        })?;
+
+        let mut depth = 0;
+        let mut documentation = None;
+        let mut soap_operation = None;
+        let mut http_operation = None;
+        let mut extensions = Vec::new();
+        let mut input = None;
+        let mut output = None;
+        let mut faults = Vec::new();
+
+        while let Some(v) = iter.next() {
+            match (v?, depth) {
+                (XmlEvent::StartElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                                name.local_name ==
+                                                                "documentation" => {
+                    documentation = Some(WsdlDocumentation::read(iter)?)
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ..
+                 },
+                 0) if name.namespace == ns_wsdl && name.local_name == "input" => {
+                    let content = MessageBinding::read("input", iter)?;
+                    input = Some(WsdlInputBinding {
+                                     documentation: content.documentation,
+                                     name: find_attribute("name", attributes),
+                                     soap_body: content.soap_body,
+                                     soap_headers: content.soap_headers,
+                                     extensions: content.extensions,
+                                     extension_attributes: extension_attributes(attributes),
+                                 });
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ..
+                 },
+                 0) if name.namespace == ns_wsdl && name.local_name == "output" => {
+                    let content = MessageBinding::read("output", iter)?;
+                    output = Some(WsdlOutputBinding {
+                                      documentation: content.documentation,
+                                      name: find_attribute("name", attributes),
+                                      soap_body: content.soap_body,
+                                      soap_headers: content.soap_headers,
+                                      extensions: content.extensions,
+                                      extension_attributes: extension_attributes(attributes),
+                                  });
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ..
+                 },
+                 0) if name.namespace == ns_wsdl && name.local_name == "fault" => {
+                    let fault_name = find_attribute("name", attributes)
+                        .ok_or_else(|| {
+                                        ErrorKind::MandatoryAttribute("name".to_string(),
+                                                                      "wsdl:fault".to_string())
+                                    })?;
+                    let content = MessageBinding::read("fault", iter)?;
+                    faults.push(WsdlFaultBinding {
+                                    documentation: content.documentation,
+                                    name: fault_name,
+                                    soap_fault: content.soap_fault,
+                                    extensions: content.extensions,
+                                    extension_attributes: extension_attributes(attributes),
+                                });
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ..
+                 },
+                 0) if is_soap_element(name, "operation") => {
+                    soap_operation = Some(SoapOperation::read(attributes)?);
+                    skip_element(iter)?;
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ..
+                 },
+                 0) if name.namespace == ns_http && name.local_name == "operation" => {
+                    http_operation = Some(HttpOperation {
+                                              method: None,
+                                              location: find_attribute("location", attributes),
+                                          });
+                    skip_element(iter)?;
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if name.namespace != ns_wsdl => {
+                    extensions.push(XmlElement::read(name, attributes, namespace, iter)?);
+                }
+                (XmlEvent::StartElement { .. }, _) => depth += 1,
+                (XmlEvent::EndElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                              name.local_name == "operation" => {
+                    break;
+                }
+                (XmlEvent::EndElement { .. }, _) => depth -= 1,
+                _ => continue,
+            }
+        }
 
         Ok(WsdlOperationBinding {
-               documentation: None,
-               name: name.ok_or_else(|| {
-                                         ErrorKind::MandatoryAttribute("name".to_string(),
-                                                                       "wsdl:operation".to_string())
-                                     })?,
-               input: Some(WsdlInputBinding{documentation: None, text: internals.clone()}),
-               output: None,
-               fault: None,
+               documentation,
+               name,
+               soap_operation,
+               http_operation,
+               extensions,
+               extension_attributes: extension_attributes(attributes),
+               input,
+               output,
+               faults,
            })
     }
 }
 
+/// Content of a binding `input`, `output` or `fault` element.
+struct MessageBinding {
+    documentation: Option<WsdlDocumentation>,
+    soap_body: Option<SoapBody>,
+    soap_headers: Vec<SoapHeader>,
+    soap_fault: Option<SoapFault>,
+    extensions: Vec<WsdlExtension>,
+}
+
+impl MessageBinding {
+    fn read(element: &str, iter: &mut Reader) -> Result<MessageBinding> {
+        let ns_wsdl = Some(NS_WSDL.to_string());
+
+        let mut depth = 0;
+        let mut content = MessageBinding {
+            documentation: None,
+            soap_body: None,
+            soap_headers: Vec::new(),
+            soap_fault: None,
+            extensions: Vec::new(),
+        };
+
+        while let Some(v) = iter.next() {
+            match (v?, depth) {
+                (XmlEvent::StartElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                                name.local_name ==
+                                                                "documentation" => {
+                    content.documentation = Some(WsdlDocumentation::read(iter)?)
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ..
+                 },
+                 0) if is_soap_element(name, "body") => {
+                    content.soap_body = Some(SoapBody::read(attributes)?);
+                    skip_element(iter)?;
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if is_soap_element(name, "header") => {
+                    content.soap_headers.push(read_soap_header(attributes, namespace, iter)?);
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ..
+                 },
+                 0) if is_soap_element(name, "fault") => {
+                    content.soap_fault = Some(SoapFault::read(attributes)?);
+                    skip_element(iter)?;
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if name.namespace != ns_wsdl => {
+                    content.extensions.push(XmlElement::read(name, attributes, namespace, iter)?);
+                }
+                (XmlEvent::StartElement { .. }, _) => depth += 1,
+                (XmlEvent::EndElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                              name.local_name == element => {
+                    break;
+                }
+                (XmlEvent::EndElement { .. }, _) => depth -= 1,
+                _ => continue,
+            }
+        }
+
+        Ok(content)
+    }
+}
+
+fn read_soap_header(attributes: &[OwnedAttribute],
+                    namespace: &Namespace,
+                    iter: &mut Reader)
+                    -> Result<SoapHeader> {
+    let mut header = SoapHeader::read(attributes, namespace)?;
+
+    let mut depth = 0;
+
+    while let Some(v) = iter.next() {
+        match (v?, depth) {
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if is_soap_element(name, "headerfault") => {
+                header.header_faults.push(SoapHeaderFault::read(attributes, namespace)?);
+                skip_element(iter)?;
+            }
+            (XmlEvent::StartElement { .. }, _) => depth += 1,
+            (XmlEvent::EndElement { .. }, 0) => break,
+            (XmlEvent::EndElement { .. }, _) => depth -= 1,
+            _ => continue,
+        }
+    }
+
+    Ok(header)
+}
+
 impl WsdlMessagePart {
-    fn read(attributes: &[OwnedAttribute]) -> Result<WsdlMessagePart> {
+    fn read(attributes: &[OwnedAttribute],
+            namespace: &Namespace,
+            iter: &mut Reader)
+            -> Result<WsdlMessagePart> {
         let part_name = find_attribute("name", attributes);
+        let element = match find_attribute("element", attributes) {
+            Some(ref element) => Some(read_qname(element, namespace)?),
+            None => None,
+        };
+        let part_type = match find_attribute("type", attributes) {
+            Some(ref part_type) => Some(read_qname(part_type, namespace)?),
+            None => None,
+        };
+
+        let (documentation, extensions) = read_documented("part", iter)?;
 
         Ok(WsdlMessagePart {
+               documentation,
                name: part_name
                    .ok_or_else(|| {
                                    ErrorKind::MandatoryAttribute("name".to_string(),
                                                                  "wsdl:part".to_string())
                                })?,
-               element: None,
-               part_type: None,
+               element,
+               part_type,
+               extensions,
+               extension_attributes: extension_attributes(attributes),
            })
     }
 }
 
 impl WsdlTypes {
-    fn read(iter: &mut Events<&[u8]>) -> Result<WsdlTypes> {
+    pub(crate) fn read(iter: &mut Reader) -> Result<WsdlTypes> {
         let ns_wsdl = Some(NS_WSDL.to_string());
 
         let mut depth = 0;
+        let mut documentation = None;
+        let mut schemas = Vec::new();
+        let mut models = Vec::new();
 
-        for event in iter {
-            match (event?, depth) {
-                (XmlEvent::StartElement { .. }, _) => depth += 1,
-                (XmlEvent::EndElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
-                                                              name.local_name == "types" => {
-                    break;
+        while let Some(v) = iter.next() {
+            match (v?, depth) {
+                (XmlEvent::StartElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                                name.local_name ==
+                                                                "documentation" => {
+                    documentation = Some(WsdlDocumentation::read(iter)?)
                 }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) => {
+                    let position = iter.source_position();
+                    let schema = XmlElement::read(name, attributes, namespace, iter)?;
+                    if is_xsd(&schema.name) && schema.name.local_name == "schema" {
+                        let mut errors = Vec::new();
+                        match XsdSchema::read_lenient(&schema, &mut errors) {
+                            Ok(model) => models.push(model),
+                            Err(e) => errors.push(e),
+                        }
+                        for e in errors {
+                            iter.skipped(e, position.clone())?;
+                        }
+                    }
+                    schemas.push(schema);
+                }
+                (XmlEvent::StartElement { .. }, _) => depth += 1,
+                (XmlEvent::EndElement { .. }, 0) => break,
                 (XmlEvent::EndElement { .. }, _) => depth -= 1,
                 _ => continue,
             }
         }
 
-        Ok(WsdlTypes { documentation: None })
+        Ok(WsdlTypes {
+               documentation,
+               target_namespace: None,
+               imported_from: None,
+               schemas,
+               models,
+           })
     }
 }
 
 impl WsdlPortType {
-    fn read(attributes: &[OwnedAttribute], iter: &mut Events<&[u8]>) -> Result<WsdlPortType> {
+    fn read(attributes: &[OwnedAttribute], iter: &mut Reader) -> Result<WsdlPortType> {
         let name = find_attribute("name", attributes)
             .ok_or_else(|| {
                             ErrorKind::MandatoryAttribute("name".to_string(),
@@ -509,9 +1365,33 @@ impl WsdlPortType {
         let ns_wsdl = Some(NS_WSDL.to_string());
 
         let mut depth = 0;
+        let mut documentation = None;
+        let mut operations = Vec::new();
+        let mut extensions = Vec::new();
 
-        for event in iter {
-            match (event?, depth) {
+        while let Some(v) = iter.next() {
+            match (v?, depth) {
+                (XmlEvent::StartElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                                name.local_name ==
+                                                                "documentation" => {
+                    documentation = Some(WsdlDocumentation::read(iter)?)
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ..
+                 },
+                 0) if name.namespace == ns_wsdl && name.local_name == "operation" => {
+                    operations.extend(iter.component(|iter| WsdlOperation::read(attributes, iter))?)
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if name.namespace != ns_wsdl => {
+                    extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
+                }
                 (XmlEvent::StartElement { .. }, _) => depth += 1,
                 (XmlEvent::EndElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
                                                               name.local_name == "portType" => {
@@ -524,23 +1404,84 @@ impl WsdlPortType {
 
         Ok(WsdlPortType {
                name,
-               documentation: None,
+               documentation,
+               target_namespace: None,
+               imported_from: None,
+               extends: Vec::new(),
+               operations,
+               extensions,
+               extension_attributes: extension_attributes(attributes),
            })
     }
 }
 
-impl WsdlDocumentation {
-    fn read(iter: &mut Events<&[u8]>) -> Result<WsdlDocumentation> {
+impl WsdlOperation {
+    fn read(attributes: &[OwnedAttribute], iter: &mut Reader) -> Result<WsdlOperation> {
+        let name = find_attribute("name", attributes)
+            .ok_or_else(|| {
+                            ErrorKind::MandatoryAttribute("name".to_string(),
+                                                          "wsdl:operation".to_string())
+                        })?;
+        let parameter_order = find_attribute("parameterOrder", attributes)
+            .map(|order| order.split_whitespace().map(String::from).collect())
+            .unwrap_or_default();
+
         let ns_wsdl = Some(NS_WSDL.to_string());
 
         let mut depth = 0;
+        let mut documentation = None;
+        let mut input = None;
+        let mut output = None;
+        let mut faults = Vec::new();
+        let mut extensions = Vec::new();
 
-        for event in iter {
-            match (event?, depth) {
+        while let Some(v) = iter.next() {
+            match (v?, depth) {
+                (XmlEvent::StartElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                                name.local_name ==
+                                                                "documentation" => {
+                    documentation = Some(WsdlDocumentation::read(iter)?)
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if name.namespace == ns_wsdl && name.local_name == "input" => {
+                    input = Some(WsdlOperationMessage::read("input", attributes, namespace, iter)?)
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if name.namespace == ns_wsdl && name.local_name == "output" => {
+                    output = Some(WsdlOperationMessage::read("output", attributes, namespace, iter)?)
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if name.namespace == ns_wsdl && name.local_name == "fault" => {
+                    let fault = WsdlOperationMessage::read("fault", attributes, namespace, iter)?;
+                    if fault.name.is_none() {
+                        bail!(ErrorKind::MandatoryAttribute("name".to_string(),
+                                                            "wsdl:fault".to_string()));
+                    }
+                    faults.push(fault)
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if name.namespace != ns_wsdl => {
+                    extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
+                }
                 (XmlEvent::StartElement { .. }, _) => depth += 1,
                 (XmlEvent::EndElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
-                                                              name.local_name ==
-                                                              "documentation" => {
+                                                              name.local_name == "operation" => {
                     break;
                 }
                 (XmlEvent::EndElement { .. }, _) => depth -= 1,
@@ -548,16 +1489,226 @@ impl WsdlDocumentation {
             }
         }
 
-        Ok(WsdlDocumentation { text: "placeholder".to_string() })
+        Ok(WsdlOperation {
+               documentation,
+               name,
+               pattern: None,
+               parameter_order,
+               input,
+               output,
+               faults,
+               extensions,
+               extension_attributes: extension_attributes(attributes),
+           })
+    }
+}
+
+impl WsdlOperationMessage {
+    fn read(element: &str,
+            attributes: &[OwnedAttribute],
+            namespace: &Namespace,
+            iter: &mut Reader)
+            -> Result<WsdlOperationMessage> {
+        let message = find_attribute("message", attributes)
+            .ok_or_else(|| {
+                            ErrorKind::MandatoryAttribute("message".to_string(),
+                                                          format!("wsdl:{}", element))
+                        })?;
+        let message = read_qname(&message, namespace)?;
+
+        let ns_wsdl = Some(NS_WSDL.to_string());
+
+        let mut depth = 0;
+        let mut documentation = None;
+        let mut extensions = Vec::new();
+
+        while let Some(v) = iter.next() {
+            match (v?, depth) {
+                (XmlEvent::StartElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                                name.local_name ==
+                                                                "documentation" => {
+                    documentation = Some(WsdlDocumentation::read(iter)?)
+                }
+                (XmlEvent::StartElement {
+                     ref name,
+                     ref attributes,
+                     ref namespace,
+                 },
+                 0) if name.namespace != ns_wsdl => {
+                    extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
+                }
+                (XmlEvent::StartElement { .. }, _) => depth += 1,
+                (XmlEvent::EndElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                              name.local_name == element => {
+                    break;
+                }
+                (XmlEvent::EndElement { .. }, _) => depth -= 1,
+                _ => continue,
+            }
+        }
+
+        Ok(WsdlOperationMessage {
+               documentation,
+               name: find_attribute("name", attributes),
+               message: Some(message),
+               element: None,
+               extensions,
+               extension_attributes: extension_attributes(attributes),
+           })
+    }
+}
+
+impl WsdlDocumentation {
+    pub(crate) fn read(iter: &mut Reader) -> Result<WsdlDocumentation> {
+        let mut depth = 0;
+        let mut text = String::new();
+        let mut markup = String::new();
+        let mut has_elements = false;
+
+        for event in iter {
+            match event? {
+                XmlEvent::StartElement {
+                    ref name,
+                    ref attributes,
+                    ..
+                } => {
+                    depth += 1;
+                    has_elements = true;
+                    markup.push('<');
+                    markup.push_str(&name.borrow().to_repr());
+                    for attr in attributes {
+                        markup.push_str(&format!(" {}=\"{}\"",
+                                                 attr.name.borrow().to_repr(),
+                                                 escape_str_attribute(&attr.value)));
+                    }
+                    markup.push('>');
+                }
+                XmlEvent::EndElement { .. } if depth == 0 => break,
+                XmlEvent::EndElement { ref name } => {
+                    depth -= 1;
+                    markup.push_str(&format!("</{}>", name.borrow().to_repr()));
+                }
+                XmlEvent::Characters(ref value) |
+                XmlEvent::CData(ref value) |
+                XmlEvent::Whitespace(ref value) => {
+                    text.push_str(value);
+                    markup.push_str(&escape_str_pcdata(value));
+                }
+                _ => continue,
+            }
+        }
+
+        Ok(WsdlDocumentation {
+               text: text.trim().to_string(),
+               markup: if has_elements {
+                   Some(markup.trim().to_string())
+               } else {
+                   None
+               },
+           })
+    }
+}
+
+/// Reads the documentation and extension elements of an element that has no
+/// other content.
+fn read_documented(element: &str,
+                   iter: &mut Reader)
+                   -> Result<(Option<WsdlDocumentation>, Vec<WsdlExtension>)> {
+    let ns_wsdl = Some(NS_WSDL.to_string());
+
+    let mut depth = 0;
+    let mut documentation = None;
+    let mut extensions = Vec::new();
+
+    while let Some(v) = iter.next() {
+        match (v?, depth) {
+            (XmlEvent::StartElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                            name.local_name == "documentation" => {
+                documentation = Some(WsdlDocumentation::read(iter)?)
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if name.namespace != ns_wsdl => {
+                extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
+            }
+            (XmlEvent::StartElement { .. }, _) => depth += 1,
+            (XmlEvent::EndElement { ref name, .. }, 0) if name.namespace == ns_wsdl &&
+                                                          name.local_name == element => {
+                break;
+            }
+            (XmlEvent::EndElement { .. }, _) => depth -= 1,
+            _ => continue,
+        }
+    }
+
+    Ok((documentation, extensions))
+}
+
+/// Attributes from foreign namespaces, kept as extensions of the element.
+pub fn extension_attributes(attributes: &[OwnedAttribute]) -> Vec<OwnedAttribute> {
+    attributes
+        .iter()
+        .filter(|a| a.name.namespace.is_some())
+        .cloned()
+        .collect()
+}
+
+/// Decodes a document to UTF-8, see `charset::decode` for how its encoding is found.
+pub fn decode_contents(bytes: &[u8]) -> Result<Vec<u8>> {
+    Ok(charset::decode(bytes)?.into_bytes())
+}
+
+/// Consumes the rest of the current element, including its end tag.
+pub fn skip_element(iter: &mut Reader) -> Result<()> {
+    let mut depth = 0;
+
+    for event in iter {
+        match (event?, depth) {
+            (XmlEvent::StartElement { .. }, _) => depth += 1,
+            (XmlEvent::EndElement { .. }, 0) => break,
+            (XmlEvent::EndElement { .. }, _) => depth -= 1,
+            _ => continue,
+        }
     }
+
+    Ok(())
 }
 
-fn decode_contents(bytes: &[u8]) -> Result<Vec<u8>> {
-    let (decoded_contents, _) = decode(bytes, DecoderTrap::Replace, UTF_8);
-    Ok(decoded_contents?.as_bytes().to_vec())
+/// Parses a QName attribute value and resolves its prefix against the
+/// namespaces in scope, unprefixed names are in the default namespace.
+pub fn read_qname(value: &str, namespace: &Namespace) -> Result<OwnedName> {
+    let mut name: OwnedName = value
+        .parse()
+        .map_err(|_| ErrorKind::InvalidQName(value.to_string()))?;
+
+    name.namespace = match name.prefix {
+        Some(ref pfx) => {
+            Some(namespace
+                     .get(pfx)
+                     .map(|x| x.to_string())
+                     .ok_or_else(|| {
+                                     ErrorKind::UndeclaredPrefix(pfx.clone(), value.to_string())
+                                 })?)
+        }
+        None => {
+            namespace
+                .get(NS_NO_PREFIX)
+                .filter(|ns| !ns.is_empty())
+                .map(String::from)
+        }
+    };
+
+    Ok(name)
 }
 
-fn find_attribute(name: &str, attributes: &[OwnedAttribute]) -> Option<String> {
+pub fn is_named(name: &OwnedName, namespace: &Option<String>, local_name: &str) -> bool {
+    name.namespace == *namespace && name.local_name == local_name
+}
+
+pub fn find_attribute(name: &str, attributes: &[OwnedAttribute]) -> Option<String> {
     attributes
         .iter()
         .find(|a| a.name.namespace.is_none() && a.name.local_name == name)
@@ -567,6 +1718,8 @@ fn find_attribute(name: &str, attributes: &[OwnedAttribute]) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use wsdl::file::example;
+    use wsdl::diagnostics::Severity;
 
     #[test]
     fn must_check_depth() {
@@ -590,4 +1743,424 @@ mod tests {
         assert_eq!("root1", wsdl.services[0].name);
         assert_eq!("root2", wsdl.services[1].name);
     }
+
+    #[test]
+    fn must_read_binding_operations() {
+        let wsdl = Wsdl::load_from_file(&example("travel_light/service.wsdl")).unwrap();
+
+        let binding = &wsdl.bindings[0];
+        assert_eq!(21, binding.operations.len());
+
+        let operation = &binding.operations[0];
+        assert_eq!("SearchFlights", operation.name);
+        assert!(operation.input.is_some());
+        assert!(operation.output.is_some());
+    }
+
+    #[test]
+    fn must_read_binding_faults() {
+        let wsdl = Wsdl::parse(r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/"
+                  xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+                  xmlns:tns="urn:test">
+    <wsdl:binding name="Binding" type="tns:PortType">
+        <wsdl:operation name="op">
+            <wsdl:input name="opRequest"><soap:body use="literal"/></wsdl:input>
+            <wsdl:fault name="first"><soap:fault name="first" use="literal"/></wsdl:fault>
+            <wsdl:fault name="second"><soap:fault name="second" use="literal"/></wsdl:fault>
+        </wsdl:operation>
+    </wsdl:binding>
+</wsdl:definitions>
+"#
+                                       .as_bytes())
+                .unwrap();
+
+        let operation = &wsdl.bindings[0].operations[0];
+        assert_eq!(Some("opRequest".to_string()), operation.input.as_ref().unwrap().name);
+        assert!(operation.output.is_none());
+        assert_eq!(2, operation.faults.len());
+        assert_eq!("second", operation.faults[1].name);
+        assert_eq!(Some(SoapUse::Literal),
+                   operation.faults[1].soap_fault.as_ref().unwrap().use_);
+    }
+
+    #[test]
+    fn must_read_soap_binding_elements() {
+        let wsdl = Wsdl::load_from_file(&example("hello_world/hello.wsdl")).unwrap();
+
+        let binding = &wsdl.bindings[0];
+        assert_eq!(SoapStyle::Rpc, binding.style());
+        assert_eq!("http://schemas.xmlsoap.org/soap/http",
+                   binding.soap_binding.as_ref().unwrap().transport);
+
+        let operation = &binding.operations[0];
+        assert_eq!(Some("sayHello"), operation.soap_action());
+        assert_eq!(SoapStyle::Rpc, binding.operation_style(operation));
+
+        let body = operation.input.as_ref().unwrap().soap_body.as_ref().unwrap();
+        assert_eq!(Some(SoapUse::Encoded), body.use_);
+        assert_eq!(Some("urn:examples:helloservice".to_string()), body.namespace);
+        assert_eq!(vec!["http://schemas.xmlsoap.org/soap/encoding/".to_string()],
+                   body.encoding_style);
+    }
+
+    #[test]
+    fn must_read_soap_headers() {
+        let wsdl = Wsdl::parse(r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/"
+                  xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+                  xmlns:tns="urn:test">
+    <wsdl:binding name="Binding" type="tns:PortType">
+        <soap:binding style="document" transport="http://schemas.xmlsoap.org/soap/http"/>
+        <wsdl:operation name="op">
+            <soap:operation soapAction="urn:op" style="rpc"/>
+            <wsdl:input>
+                <soap:header use="literal" part="session" message="tns:Session">
+                    <soap:headerfault use="literal" part="fault" message="tns:SessionFault"/>
+                </soap:header>
+                <soap:body use="literal" parts="body"/>
+            </wsdl:input>
+        </wsdl:operation>
+    </wsdl:binding>
+</wsdl:definitions>
+"#
+                                       .as_bytes())
+                .unwrap();
+
+        let binding = &wsdl.bindings[0];
+        let operation = &binding.operations[0];
+        assert_eq!(SoapStyle::Document, binding.style());
+        assert_eq!(SoapStyle::Rpc, binding.operation_style(operation));
+
+        let input = operation.input.as_ref().unwrap();
+        assert_eq!(Some(vec!["body".to_string()]),
+                   input.soap_body.as_ref().unwrap().parts);
+
+        let header = &input.soap_headers[0];
+        assert_eq!("session", header.part);
+        assert_eq!(Some("urn:test".to_string()), header.message.namespace);
+        assert_eq!("SessionFault", header.header_faults[0].message.local_name);
+    }
+
+    #[test]
+    fn must_distinguish_soap_versions() {
+        let wsdl = Wsdl::load_from_file(&example("travel_light/service.wsdl")).unwrap();
+
+        assert_eq!(Some(SoapVersion::Soap11), wsdl.bindings[0].soap_version());
+        assert_eq!(Some(SoapVersion::Soap12), wsdl.bindings[1].soap_version());
+
+        let operation = &wsdl.bindings[1].operations[0];
+        assert_eq!(Some("ElsyArres.API/SearchFlights"), operation.soap_action());
+        assert_eq!(Some(SoapUse::Literal),
+                   operation.input.as_ref().unwrap().soap_body.as_ref().unwrap().use_);
+    }
+
+    #[test]
+    fn must_read_port_addresses() {
+        let wsdl = Wsdl::load_from_file(&example("hello_world/hello.wsdl")).unwrap();
+
+        let service = &wsdl.services[0];
+        let port = service.find_port("Hello_Port").unwrap();
+        assert_eq!(AddressProtocol::Soap11, port.address.as_ref().unwrap().protocol);
+        assert_eq!(Some("http://localhost:8080/soap/servlet/rpcrouter"),
+                   service.endpoint("Hello_Port"));
+        assert_eq!(None, service.endpoint("Missing_Port"));
+    }
+
+    #[test]
+    fn must_read_soap12_and_http_addresses() {
+        let wsdl = Wsdl::parse(r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/"
+                  xmlns:soap12="http://schemas.xmlsoap.org/wsdl/soap12/"
+                  xmlns:http="http://schemas.xmlsoap.org/wsdl/http/"
+                  xmlns:tns="urn:test">
+    <wsdl:service name="Service">
+        <wsdl:port name="Soap12" binding="tns:Soap12Binding">
+            <soap12:address location="http://example.com/soap12"/>
+        </wsdl:port>
+        <wsdl:port name="Http" binding="tns:HttpBinding">
+            <http:address location="http://example.com/http"/>
+        </wsdl:port>
+    </wsdl:service>
+</wsdl:definitions>
+"#
+                                       .as_bytes())
+                .unwrap();
+
+        let ports = &wsdl.services[0].ports;
+        assert_eq!(AddressProtocol::Soap12, ports[0].address.as_ref().unwrap().protocol);
+        assert_eq!(AddressProtocol::Http, ports[1].address.as_ref().unwrap().protocol);
+        assert_eq!("http://example.com/http", ports[1].address.as_ref().unwrap().location);
+    }
+
+    #[test]
+    fn must_resolve_message_parts() {
+        let wsdl = Wsdl::load_from_file(&example("hello_world/hello.wsdl")).unwrap();
+
+        let part = &wsdl.messages[0].parts[0];
+        let part_type = part.part_type.as_ref().unwrap();
+        assert_eq!("firstName", part.name);
+        assert_eq!("string", part_type.local_name);
+        assert_eq!(Some("http://www.w3.org/2001/XMLSchema".to_string()),
+                   part_type.namespace);
+        assert!(part.element.is_none());
+
+        let wsdl = Wsdl::load_from_file(&example("travel_light/service.wsdl")).unwrap();
+
+        let part = &wsdl.messages[0].parts[0];
+        let element = part.element.as_ref().unwrap();
+        assert_eq!("SearchFlights", element.local_name);
+        assert_eq!(Some("ElsyArres.API".to_string()), element.namespace);
+    }
+
+    #[test]
+    fn must_apply_default_namespace_to_qnames() {
+        let wsdl = Wsdl::parse(r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/" xmlns="urn:x"
+                  targetNamespace="urn:x">
+    <wsdl:message name="Request">
+        <wsdl:part name="body" element="Body"/>
+    </wsdl:message>
+    <wsdl:service name="Service">
+        <wsdl:port name="Port" binding="Binding"/>
+    </wsdl:service>
+</wsdl:definitions>
+"#
+                                       .as_bytes())
+                .unwrap();
+
+        let element = wsdl.messages[0].parts[0].element.as_ref().unwrap();
+        assert_eq!(Some("urn:x".to_string()), element.namespace);
+        assert_eq!(Some("urn:x".to_string()),
+                   wsdl.services[0].ports[0].binding.namespace);
+    }
+
+    #[test]
+    fn must_fail_on_undeclared_prefix() {
+        let result = Wsdl::parse(r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/">
+    <wsdl:message name="Request">
+        <wsdl:part name="body" element="missing:Body"/>
+    </wsdl:message>
+</wsdl:definitions>
+"#
+                                         .as_bytes());
+
+        match result {
+            Err(Error(ErrorKind::UndeclaredPrefix(ref prefix, _), _)) => assert_eq!("missing", prefix),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn must_skip_broken_components_in_lenient_mode() {
+        let contents = r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/" xmlns:tns="urn:test">
+    <wsdl:message name="Request">
+        <wsdl:part name="body" element="missing:Body"/>
+        <wsdl:part name="header" element="tns:Header"/>
+    </wsdl:message>
+    <wsdl:portType>
+        <wsdl:operation name="Ping"/>
+    </wsdl:portType>
+    <wsdl:unknown/>
+    <wsdl:service name="Service"/>
+</wsdl:definitions>
+"#
+                .as_bytes();
+
+        assert!(Wsdl::parse(contents).is_err());
+
+        let (wsdl, diagnostics) = Wsdl::parse_lenient(contents).unwrap();
+        assert_eq!(1, wsdl.messages[0].parts.len());
+        assert_eq!("header", wsdl.messages[0].parts[0].name);
+        assert!(wsdl.port_types.is_empty());
+        assert_eq!("Service", wsdl.services[0].name);
+
+        let diagnostics = diagnostics
+            .iter()
+            .map(|d| (d.severity, d.position.as_ref().unwrap().line, d.kind.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(Severity::Error,
+                         4,
+                         "Namespace prefix `missing` of `missing:Body` is not declared"
+                             .to_string()),
+                        (Severity::Error,
+                         7,
+                         "Attribute `name` is mandatory for `wsdl:portType` element".to_string()),
+                        (Severity::Warning, 10, "Invalid `wsdl:unknown` element".to_string())],
+                   diagnostics);
+    }
+
+    #[test]
+    fn must_skip_broken_schema_components() {
+        let contents = r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/">
+    <wsdl:types>
+        <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:test">
+            <xs:element type="xs:string"/>
+            <xs:element name="Ping" type="xs:string"/>
+        </xs:schema>
+    </wsdl:types>
+    <wsdl:service name="Service"/>
+</wsdl:definitions>
+"#
+                .as_bytes();
+
+        let error = Wsdl::parse(contents).unwrap_err();
+        match *error.kind() {
+            ErrorKind::MandatoryAttribute(ref attribute, ref element) => {
+                assert_eq!(("name", "xs:element"), (attribute.as_str(), element.as_str()))
+            }
+            ref other => panic!("Unexpected error: {:?}", other),
+        }
+        assert_eq!(Some(&SourcePosition::new(4, 9)), error.position());
+
+        let (wsdl, diagnostics) = Wsdl::parse_lenient(contents).unwrap();
+        let models = &wsdl.types[0].models;
+        assert_eq!(1, models.len());
+        assert_eq!("Ping", models[0].elements[0].name);
+        assert_eq!(1, models[0].elements.len());
+        assert_eq!("Service", wsdl.services[0].name);
+
+        let diagnostics = diagnostics
+            .iter()
+            .map(|d| (d.severity, d.position.clone(), d.kind.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(Severity::Error,
+                         Some(SourcePosition::new(4, 9)),
+                         "Attribute `name` is mandatory for `xs:element` element".to_string())],
+                   diagnostics);
+    }
+
+    #[test]
+    fn must_read_documentation() {
+        let wsdl = Wsdl::load_from_file(&example("hello_world/hello.wsdl")).unwrap();
+
+        let documentation = wsdl.services[0].documentation.as_ref().unwrap();
+        assert_eq!("WSDL File for HelloService", documentation.text);
+        assert!(documentation.markup.is_none());
+
+        let wsdl = Wsdl::parse(r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/" xmlns:tns="urn:test">
+    <wsdl:documentation>Definitions</wsdl:documentation>
+    <wsdl:message name="Request">
+        <wsdl:documentation>Request <b class="x">message</b> &amp; more</wsdl:documentation>
+        <wsdl:part name="body" type="tns:Body">
+            <wsdl:documentation>Body part</wsdl:documentation>
+        </wsdl:part>
+    </wsdl:message>
+    <wsdl:portType name="PortType">
+        <wsdl:documentation>Port type</wsdl:documentation>
+    </wsdl:portType>
+    <wsdl:binding name="Binding" type="tns:PortType">
+        <wsdl:documentation>Binding</wsdl:documentation>
+    </wsdl:binding>
+</wsdl:definitions>
+"#
+                                       .as_bytes())
+                .unwrap();
+
+        let text = |d: &Option<WsdlDocumentation>| d.as_ref().unwrap().text.clone();
+        assert_eq!("Definitions", text(&wsdl.documentation));
+        assert_eq!("Request message & more", text(&wsdl.messages[0].documentation));
+        assert_eq!(Some("Request <b class=\"x\">message</b> &amp; more".to_string()),
+                   wsdl.messages[0].documentation.as_ref().unwrap().markup);
+        assert_eq!("Body part", text(&wsdl.messages[0].parts[0].documentation));
+        assert_eq!("Port type", text(&wsdl.port_types[0].documentation));
+        assert_eq!("Binding", text(&wsdl.bindings[0].documentation));
+    }
+
+    #[test]
+    fn must_read_port_type_operations() {
+        let wsdl = Wsdl::load_from_file(&example("hello_world/hello.wsdl")).unwrap();
+
+        let port_type = &wsdl.port_types[0];
+        assert_eq!("Hello_PortType", port_type.name);
+        assert_eq!(1, port_type.operations.len());
+
+        let operation = &port_type.operations[0];
+        let input = operation.input.as_ref().unwrap();
+        let output = operation.output.as_ref().unwrap();
+        assert_eq!("sayHello", operation.name);
+        let input_message = input.message.as_ref().unwrap();
+        assert_eq!("SayHelloRequest", input_message.local_name);
+        assert_eq!(Some("http://www.ecerami.com/wsdl/HelloService.wsdl".to_string()),
+                   input_message.namespace);
+        assert_eq!("SayHelloResponse", output.message.as_ref().unwrap().local_name);
+        assert!(operation.faults.is_empty());
+    }
+
+    #[test]
+    fn must_read_operation_faults_and_parameter_order() {
+        let wsdl = Wsdl::parse(r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/" xmlns:tns="urn:test">
+    <wsdl:portType name="PortType">
+        <wsdl:operation name="op" parameterOrder="a b">
+            <wsdl:input name="opRequest" message="tns:Request"/>
+            <wsdl:output message="tns:Response"/>
+            <wsdl:fault name="first" message="tns:FirstFault"/>
+            <wsdl:fault name="second" message="tns:SecondFault"/>
+        </wsdl:operation>
+    </wsdl:portType>
+</wsdl:definitions>
+"#
+                                       .as_bytes())
+                .unwrap();
+
+        let operation = &wsdl.port_types[0].operations[0];
+        assert_eq!(vec!["a".to_string(), "b".to_string()], operation.parameter_order);
+        assert_eq!(Some("opRequest".to_string()), operation.input.as_ref().unwrap().name);
+        assert_eq!(2, operation.faults.len());
+        assert_eq!(Some("second".to_string()), operation.faults[1].name);
+        assert_eq!(Some("urn:test".to_string()),
+                   operation.faults[1].message.as_ref().unwrap().namespace);
+    }
+
+    #[test]
+    fn must_keep_extension_elements_and_attributes() {
+        let wsdl = Wsdl::parse(r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/"
+                  xmlns:soap="http://schemas.xmlsoap.org/wsdl/soap/"
+                  xmlns:wsp="http://www.w3.org/ns/ws-policy"
+                  xmlns:ext="urn:ext" xmlns:tns="urn:test" targetNamespace="urn:test">
+    <wsp:Policy ext:id="policy">
+        <wsp:ExactlyOne><ext:Assertion>on</ext:Assertion></wsp:ExactlyOne>
+    </wsp:Policy>
+    <wsdl:message name="Request" ext:kind="request">
+        <wsdl:part name="body" element="tns:Body">
+            <ext:note>Body part</ext:note>
+        </wsdl:part>
+    </wsdl:message>
+    <wsdl:service name="Service">
+        <wsdl:port name="Port" binding="tns:Binding">
+            <soap:address location="http://example.com"/>
+            <ext:address producer="producer"/>
+        </wsdl:port>
+    </wsdl:service>
+</wsdl:definitions>
+"#
+                                       .as_bytes())
+                .unwrap();
+
+        let policy = &wsdl.extensions[0];
+        assert_eq!("Policy", policy.name.local_name);
+        assert_eq!("on", policy.text());
+        assert_eq!("Assertion", policy.elements()[0].elements()[0].name.local_name);
+        assert_eq!("policy", policy.attributes[0].value);
+
+        let message = &wsdl.messages[0];
+        assert_eq!("kind", message.extension_attributes[0].name.local_name);
+        assert_eq!("Body part", message.parts[0].extensions[0].text());
+
+        let port = &wsdl.services[0].ports[0];
+        assert_eq!("http://example.com", port.address.as_ref().unwrap().location);
+        assert_eq!(Some("producer"), port.extensions[0].attribute("producer"));
+
+        let reread = Wsdl::parse(wsdl.to_xml().as_bytes()).unwrap();
+        assert_eq!(wsdl.extensions, reread.extensions);
+        assert_eq!(format!("{:?}", wsdl.messages), format!("{:?}", reread.messages));
+        assert_eq!(format!("{:?}", wsdl.services), format!("{:?}", reread.services));
+    }
+
 }
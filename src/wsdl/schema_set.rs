@@ -0,0 +1,593 @@
+use super::diagnostics::{Diagnostic, Severity};
+use super::errors::*;
+use super::import::NS_XSD;
+use super::pattern::Pattern;
+use super::xsd::*;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use xml::name::OwnedName;
+
+/// Schemas looked up together, components of one refer to the ones of the
+/// others by QName.
+#[derive(Debug, Clone)]
+pub struct SchemaSet<'a> {
+    schemas: Vec<&'a XsdSchema>,
+    /// `pattern` facets compiled so far, by their source.
+    patterns: RefCell<HashMap<String, Pattern>>,
+}
+
+/// Complex type with the content model and attributes of its base types
+/// merged in.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct XsdEffectiveType {
+    pub is_abstract: bool,
+    pub mixed: bool,
+    /// Base types, from the direct base to the root of the derivation.
+    pub bases: Vec<OwnedName>,
+    /// Simple type of the character content of types with simple content.
+    pub simple_content: Option<OwnedName>,
+    /// Elements of the base types come first, `None` for empty content.
+    pub group: Option<XsdModelGroup>,
+    pub attributes: Vec<XsdAttribute>,
+    pub any_attribute: bool,
+}
+
+impl<'a> SchemaSet<'a> {
+    pub fn new(schemas: Vec<&'a XsdSchema>) -> SchemaSet<'a> {
+        SchemaSet {
+            schemas,
+            patterns: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn schemas(&self) -> &[&'a XsdSchema] {
+        &self.schemas
+    }
+
+    /// Matches `value` against a `pattern` facet, each pattern is compiled
+    /// once per set.
+    pub(crate) fn is_match(&self, pattern: &str, value: &str) -> Result<bool> {
+        if let Some(compiled) = self.patterns.borrow().get(pattern) {
+            return Ok(compiled.is_match(value));
+        }
+
+        let compiled = Pattern::new(pattern)?;
+        let matched = compiled.is_match(value);
+        self.patterns
+            .borrow_mut()
+            .insert(pattern.to_string(), compiled);
+        Ok(matched)
+    }
+
+    fn in_namespace(&self, namespace: &Option<String>) -> Vec<&'a XsdSchema> {
+        self.schemas
+            .iter()
+            .filter(|s| s.target_namespace == *namespace)
+            .cloned()
+            .collect()
+    }
+
+    pub fn find_element(&self, name: &OwnedName) -> Option<&'a XsdElement> {
+        self.in_namespace(&name.namespace)
+            .into_iter()
+            .filter_map(|s| s.find_element(&name.local_name))
+            .next()
+    }
+
+    pub fn find_complex_type(&self, name: &OwnedName) -> Option<&'a XsdComplexType> {
+        self.in_namespace(&name.namespace)
+            .into_iter()
+            .filter_map(|s| s.find_complex_type(&name.local_name))
+            .next()
+    }
+
+    pub fn find_simple_type(&self, name: &OwnedName) -> Option<&'a XsdSimpleType> {
+        self.in_namespace(&name.namespace)
+            .into_iter()
+            .filter_map(|s| s.find_simple_type(&name.local_name))
+            .next()
+    }
+
+    pub fn find_group(&self, name: &OwnedName) -> Option<&'a XsdGroup> {
+        self.in_namespace(&name.namespace)
+            .into_iter()
+            .filter_map(|s| s.find_group(&name.local_name))
+            .next()
+    }
+
+    pub fn find_attribute_group(&self, name: &OwnedName) -> Option<&'a XsdAttributeGroup> {
+        self.in_namespace(&name.namespace)
+            .into_iter()
+            .filter_map(|s| s.find_attribute_group(&name.local_name))
+            .next()
+    }
+
+    /// Effective content model and attributes of `complex_type`. Extensions
+    /// append their elements and attributes to the ones of the base,
+    /// restrictions replace the elements and override or prohibit attributes.
+    /// References to named groups and attribute groups are expanded in place.
+    ///
+    /// Fails with the first reference that is circular or not defined.
+    pub fn effective_type(&self, complex_type: &XsdComplexType) -> Result<XsdEffectiveType> {
+        let (effective, diagnostics) = self.effective_type_lenient(complex_type);
+        match diagnostics.into_iter().next() {
+            Some(diagnostic) => Err(diagnostic.kind.into()),
+            None => Ok(effective),
+        }
+    }
+
+    /// Like `effective_type`, but references that are circular or not
+    /// defined are left out of the effective type and reported next to it as
+    /// errors without a position.
+    pub fn effective_type_lenient(&self,
+                                  complex_type: &XsdComplexType)
+                                  -> (XsdEffectiveType, Vec<Diagnostic>) {
+        let mut errors = Vec::new();
+        let effective = self.derive(complex_type, &mut Vec::new(), &mut errors);
+        let diagnostics = errors
+            .into_iter()
+            .map(|kind| Diagnostic::new(Severity::Error, kind, None))
+            .collect();
+        (effective, diagnostics)
+    }
+
+    fn derive(&self,
+              complex_type: &XsdComplexType,
+              chain: &mut Vec<OwnedName>,
+              errors: &mut Vec<ErrorKind>)
+              -> XsdEffectiveType {
+        let referrer = referrer(complex_type);
+        let mut effective = match complex_type.content {
+            XsdContent::Empty => XsdEffectiveType::default(),
+            XsdContent::Group(ref group) => {
+                let group = self.expand_group(group, &referrer, &mut Vec::new(), errors);
+                XsdEffectiveType { group: Some(group), ..XsdEffectiveType::default() }
+            }
+            XsdContent::Simple(ref derivation) |
+            XsdContent::Complex(ref derivation) => {
+                let mut base = self.base(complex_type, &derivation.base, chain, errors);
+                let group = derivation
+                    .group
+                    .as_ref()
+                    .map(|g| self.expand_group(g, &referrer, &mut Vec::new(), errors));
+                match derivation.method {
+                    XsdDerivationMethod::Extension => {
+                        base.group = extend(base.group.take(), group);
+                    }
+                    XsdDerivationMethod::Restriction => {
+                        if let XsdContent::Complex(_) = complex_type.content {
+                            base.group = group;
+                        }
+                        base.any_attribute = false;
+                    }
+                }
+                base.bases.insert(0, derivation.base.clone());
+                base
+            }
+        };
+
+        let mut attributes = complex_type.attributes.clone();
+        let mut any_attribute = complex_type.any_attribute;
+        self.expand_attribute_groups(&complex_type.attribute_groups,
+                                     &referrer,
+                                     &mut Vec::new(),
+                                     &mut attributes,
+                                     &mut any_attribute,
+                                     errors);
+
+        for attribute in attributes {
+            effective
+                .attributes
+                .retain(|a| a.name != attribute.name || a.namespace != attribute.namespace);
+            if attribute.attribute_use != XsdAttributeUse::Prohibited {
+                effective.attributes.push(attribute);
+            }
+        }
+
+        effective.is_abstract = complex_type.is_abstract;
+        effective.mixed = complex_type.mixed;
+        effective.any_attribute |= any_attribute;
+        effective
+    }
+
+    /// Effective type of a base type, simple types become the simple content.
+    fn base(&self,
+            complex_type: &XsdComplexType,
+            name: &OwnedName,
+            chain: &mut Vec<OwnedName>,
+            errors: &mut Vec<ErrorKind>)
+            -> XsdEffectiveType {
+        if chain.contains(name) {
+            errors.push(circular("Type", chain, name));
+            return XsdEffectiveType::default();
+        }
+
+        let is_xsd = name.namespace.as_deref() == Some(NS_XSD);
+        if is_xsd && name.local_name == "anyType" {
+            return XsdEffectiveType::default();
+        }
+
+        if let Some(base) = self.find_complex_type(name) {
+            chain.push(name.clone());
+            let effective = self.derive(base, chain, errors);
+            chain.pop();
+            return effective;
+        }
+
+        if is_xsd || self.find_simple_type(name).is_some() {
+            return XsdEffectiveType {
+                       simple_content: Some(name.clone()),
+                       ..XsdEffectiveType::default()
+                   };
+        }
+
+        errors.push(unresolved("Type", name, &referrer(complex_type)));
+        XsdEffectiveType::default()
+    }
+
+    /// Copy of `group` with the named groups it references in place, each
+    /// taking the occurrence bounds of its reference.
+    fn expand_group(&self,
+                    group: &XsdModelGroup,
+                    referrer: &str,
+                    chain: &mut Vec<OwnedName>,
+                    errors: &mut Vec<ErrorKind>)
+                    -> XsdModelGroup {
+        let mut particles = Vec::new();
+
+        for particle in &group.particles {
+            match *particle {
+                XsdParticle::Group(ref nested) => {
+                    particles.push(XsdParticle::Group(self.expand_group(nested,
+                                                                        referrer,
+                                                                        chain,
+                                                                        errors)))
+                }
+                XsdParticle::GroupRef(ref group_ref) => {
+                    let name = &group_ref.reference;
+                    if chain.contains(name) {
+                        errors.push(circular("Group", chain, name));
+                        continue;
+                    }
+
+                    match self.find_group(name) {
+                        Some(definition) => {
+                            chain.push(name.clone());
+                            let mut expanded =
+                                self.expand_group(&definition.group, referrer, chain, errors);
+                            chain.pop();
+                            expanded.min_occurs = group_ref.min_occurs;
+                            expanded.max_occurs = group_ref.max_occurs;
+                            particles.push(XsdParticle::Group(expanded));
+                        }
+                        None => {
+                            let referrer = chain.last().map_or(referrer, |n| n.local_name.as_str());
+                            errors.push(unresolved("Group", name, referrer));
+                        }
+                    }
+                }
+                ref other => particles.push(other.clone()),
+            }
+        }
+
+        XsdModelGroup {
+            compositor: group.compositor,
+            min_occurs: group.min_occurs,
+            max_occurs: group.max_occurs,
+            particles,
+        }
+    }
+
+    /// Adds the attributes of the named attribute groups, and of the groups
+    /// these reference, to `attributes`.
+    fn expand_attribute_groups(&self,
+                               names: &[OwnedName],
+                               referrer: &str,
+                               chain: &mut Vec<OwnedName>,
+                               attributes: &mut Vec<XsdAttribute>,
+                               any_attribute: &mut bool,
+                               errors: &mut Vec<ErrorKind>) {
+        for name in names {
+            if chain.contains(name) {
+                errors.push(circular("Attribute group", chain, name));
+                continue;
+            }
+
+            match self.find_attribute_group(name) {
+                Some(group) => {
+                    attributes.extend(group.attributes.iter().cloned());
+                    *any_attribute |= group.any_attribute;
+                    chain.push(name.clone());
+                    self.expand_attribute_groups(&group.attribute_groups,
+                                                 referrer,
+                                                 chain,
+                                                 attributes,
+                                                 any_attribute,
+                                                 errors);
+                    chain.pop();
+                }
+                None => {
+                    let referrer = chain.last().map_or(referrer, |n| n.local_name.as_str());
+                    errors.push(unresolved("Attribute group", name, referrer));
+                }
+            }
+        }
+    }
+}
+
+fn referrer(complex_type: &XsdComplexType) -> String {
+    complex_type
+        .name
+        .clone()
+        .unwrap_or_else(|| "anonymous type".to_string())
+}
+
+fn circular(kind: &str, chain: &[OwnedName], name: &OwnedName) -> ErrorKind {
+    let mut names = chain.iter().map(|n| n.local_name.clone()).collect::<Vec<_>>();
+    names.push(name.local_name.clone());
+    ErrorKind::CircularReference(kind.to_string(), names)
+}
+
+fn unresolved(kind: &str, name: &OwnedName, referrer: &str) -> ErrorKind {
+    ErrorKind::UnresolvedReference(kind.to_string(), name.local_name.clone(), referrer.to_string())
+}
+
+/// Content of an extension, a sequence of the base content and the added one.
+fn extend(base: Option<XsdModelGroup>, added: Option<XsdModelGroup>) -> Option<XsdModelGroup> {
+    let (mut base, added) = match (base, added) {
+        (Some(base), Some(added)) => (base, added),
+        (base, None) => return base,
+        (None, added) => return added,
+    };
+
+    if is_plain_sequence(&base) && is_plain_sequence(&added) {
+        base.particles.extend(added.particles);
+        return Some(base);
+    }
+
+    Some(XsdModelGroup {
+             compositor: XsdCompositor::Sequence,
+             min_occurs: 1,
+             max_occurs: Some(1),
+             particles: vec![XsdParticle::Group(base), XsdParticle::Group(added)],
+         })
+}
+
+fn is_plain_sequence(group: &XsdModelGroup) -> bool {
+    group.compositor == XsdCompositor::Sequence && group.min_occurs == 1 &&
+    group.max_occurs == Some(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::file::example;
+    use super::super::element::XmlElement;
+    use super::super::schema::Wsdl;
+
+    fn element_names(group: &Option<XsdModelGroup>) -> Vec<&str> {
+        group
+            .as_ref()
+            .map(|g| &g.particles[..])
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(|p| match *p {
+                            XsdParticle::Element(ref element) => Some(element.name.as_str()),
+                            _ => None,
+                        })
+            .collect()
+    }
+
+    fn schema(body: &str) -> XsdSchema {
+        let schema = format!(r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns:tns="urn:test" targetNamespace="urn:test">{}</xs:schema>"#,
+                             body);
+        XsdSchema::read(&XmlElement::parse(schema.as_bytes()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn extends_complex_content_test() {
+        let wsdl = Wsdl::load_from_file(&example("travel_light/service.wsdl")).unwrap();
+        let schemas = wsdl.schema_set();
+
+        let name = OwnedName::qualified("SearchFlights", "ElsyArres.API", None::<&str>);
+        let search = schemas
+            .effective_type(schemas.find_complex_type(&name).unwrap())
+            .unwrap();
+
+        assert!(!search.is_abstract);
+        assert_eq!(vec!["SoapMessageBase"],
+                   search.bases.iter().map(|b| b.local_name.as_str()).collect::<Vec<_>>());
+        assert_eq!(vec!["Username",
+                        "Password",
+                        "LanguageCode",
+                        "ErrorMessage",
+                        "ErrorCode",
+                        "AppVersion",
+                        "Request",
+                        "Response"],
+                   element_names(&search.group));
+
+        let base = OwnedName::qualified("SoapMessageBase", "ElsyArres.API", None::<&str>);
+        assert!(schemas
+                    .effective_type(schemas.find_complex_type(&base).unwrap())
+                    .unwrap()
+                    .is_abstract);
+    }
+
+    #[test]
+    fn derives_simple_content_and_attributes_test() {
+        let schema = schema(r#"
+    <xs:complexType name="Price">
+        <xs:simpleContent>
+            <xs:extension base="xs:decimal">
+                <xs:attribute name="currency" type="xs:string"/>
+                <xs:attribute name="rounded" type="xs:boolean"/>
+            </xs:extension>
+        </xs:simpleContent>
+    </xs:complexType>
+    <xs:complexType name="FixedPrice">
+        <xs:simpleContent>
+            <xs:restriction base="tns:Price">
+                <xs:attribute name="currency" type="xs:string" use="required"/>
+                <xs:attribute name="rounded" use="prohibited"/>
+            </xs:restriction>
+        </xs:simpleContent>
+    </xs:complexType>"#);
+        let schemas = SchemaSet::new(vec![&schema]);
+
+        let fixed = schemas
+            .effective_type(schema.find_complex_type("FixedPrice").unwrap())
+            .unwrap();
+
+        assert_eq!(vec!["Price", "decimal"],
+                   fixed.bases.iter().map(|b| b.local_name.as_str()).collect::<Vec<_>>());
+        assert_eq!(Some("decimal"),
+                   fixed.simple_content.as_ref().map(|s| s.local_name.as_str()));
+        assert_eq!(1, fixed.attributes.len());
+        assert_eq!(XsdAttributeUse::Required, fixed.attributes[0].attribute_use);
+    }
+
+    #[test]
+    fn reports_derivation_cycles_test() {
+        let schema = schema(r#"
+    <xs:complexType name="A">
+        <xs:complexContent><xs:extension base="tns:B"/></xs:complexContent>
+    </xs:complexType>
+    <xs:complexType name="B">
+        <xs:complexContent><xs:extension base="tns:A"/></xs:complexContent>
+    </xs:complexType>
+    <xs:complexType name="C">
+        <xs:complexContent><xs:extension base="tns:Missing"/></xs:complexContent>
+    </xs:complexType>"#);
+        let schemas = SchemaSet::new(vec![&schema]);
+
+        match schemas.effective_type(schema.find_complex_type("A").unwrap()) {
+            Err(Error(ErrorKind::CircularReference(_, ref chain), _)) => {
+                assert_eq!(vec!["B", "A", "B"], *chain)
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+        match schemas.effective_type(schema.find_complex_type("C").unwrap()) {
+            Err(Error(ErrorKind::UnresolvedReference(_, ref name, _), _)) => {
+                assert_eq!("Missing", name)
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn expands_group_references_test() {
+        let common = XmlElement::parse(r#"
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns="urn:common" targetNamespace="urn:common">
+    <xs:attributeGroup name="Marking">
+        <xs:attribute name="marking" type="xs:string"/>
+    </xs:attributeGroup>
+    <xs:attributeGroup name="Audit">
+        <xs:attribute name="created" type="xs:dateTime"/>
+        <xs:attributeGroup ref="Marking"/>
+        <xs:anyAttribute/>
+    </xs:attributeGroup>
+</xs:schema>"#
+                                               .as_bytes())
+                .unwrap();
+        let common = XsdSchema::read(&common).unwrap();
+        let schema = schema(r#"
+    <xs:group name="Payment">
+        <xs:sequence>
+            <xs:element name="Amount" type="xs:decimal"/>
+            <xs:group ref="tns:Card" minOccurs="0"/>
+        </xs:sequence>
+    </xs:group>
+    <xs:group name="Card">
+        <xs:choice><xs:element name="Number" type="xs:string"/></xs:choice>
+    </xs:group>
+    <xs:complexType name="Order" xmlns:c="urn:common">
+        <xs:sequence>
+            <xs:element name="Id" type="xs:string"/>
+            <xs:group ref="tns:Payment" maxOccurs="unbounded"/>
+        </xs:sequence>
+        <xs:attribute name="id" type="xs:ID"/>
+        <xs:attributeGroup ref="c:Audit"/>
+    </xs:complexType>"#);
+        let schemas = SchemaSet::new(vec![&schema, &common]);
+
+        let order = schemas
+            .effective_type(schema.find_complex_type("Order").unwrap())
+            .unwrap();
+
+        assert_eq!(vec!["id", "created", "marking"],
+                   order
+                       .attributes
+                       .iter()
+                       .map(|a| a.name.as_str())
+                       .collect::<Vec<_>>());
+        assert!(order.any_attribute);
+        let particles = &order.group.as_ref().unwrap().particles;
+        match particles[1] {
+            XsdParticle::Group(ref payment) => {
+                assert_eq!((1, None), (payment.min_occurs, payment.max_occurs));
+                assert_eq!(vec!["Amount"], element_names(&Some(payment.clone())));
+                match payment.particles[1] {
+                    XsdParticle::Group(ref card) => {
+                        assert_eq!(XsdCompositor::Choice, card.compositor);
+                        assert_eq!(0, card.min_occurs);
+                    }
+                    ref other => panic!("Unexpected particle: {:?}", other),
+                }
+            }
+            ref other => panic!("Unexpected particle: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_group_cycles_and_missing_groups_test() {
+        let schema = schema(r#"
+    <xs:group name="A"><xs:sequence><xs:group ref="tns:B"/></xs:sequence></xs:group>
+    <xs:group name="B">
+        <xs:sequence>
+            <xs:element name="Item" type="xs:string"/>
+            <xs:group ref="tns:A"/>
+        </xs:sequence>
+    </xs:group>
+    <xs:attributeGroup name="Loop"><xs:attributeGroup ref="tns:Loop"/></xs:attributeGroup>
+    <xs:complexType name="T">
+        <xs:group ref="tns:A"/>
+        <xs:attribute name="kept" type="xs:string"/>
+        <xs:attributeGroup ref="tns:Loop"/>
+        <xs:attributeGroup ref="tns:Missing"/>
+    </xs:complexType>"#);
+        let schemas = SchemaSet::new(vec![&schema]);
+        let complex_type = schema.find_complex_type("T").unwrap();
+
+        let (effective, diagnostics) = schemas.effective_type_lenient(complex_type);
+        assert_eq!(1, effective.attributes.len());
+        assert!(diagnostics
+                    .iter()
+                    .all(|d| d.severity == Severity::Error && d.position.is_none()));
+        let errors = diagnostics.iter().map(|d| &d.kind).collect::<Vec<_>>();
+        assert_eq!(3, errors.len());
+        match *errors[0] {
+            ErrorKind::CircularReference(ref kind, ref chain) => {
+                assert_eq!("Group", kind);
+                assert_eq!(vec!["A", "B", "A"], *chain);
+            }
+            ref other => panic!("Unexpected error: {:?}", other),
+        }
+        match *errors[1] {
+            ErrorKind::CircularReference(ref kind, _) => assert_eq!("Attribute group", kind),
+            ref other => panic!("Unexpected error: {:?}", other),
+        }
+        match *errors[2] {
+            ErrorKind::UnresolvedReference(_, ref name, ref referrer) => {
+                assert_eq!(("Missing", "T"), (name.as_str(), referrer.as_str()))
+            }
+            ref other => panic!("Unexpected error: {:?}", other),
+        }
+
+        assert!(schemas.effective_type(complex_type).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use super::errors::*;
+use super::schema::{find_attribute, read_qname};
+
+use xml::attribute::OwnedAttribute;
+use xml::name::OwnedName;
+use xml::namespace::Namespace;
+
+pub const NS_WSDL_SOAP: &str = "http://schemas.xmlsoap.org/wsdl/soap/";
+pub const NS_WSDL_SOAP12: &str = "http://schemas.xmlsoap.org/wsdl/soap12/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoapVersion {
+    Soap11,
+    Soap12,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoapStyle {
+    Rpc,
+    Document,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoapUse {
+    Literal,
+    Encoded,
+}
+
+/// `soap:binding` or `soap12:binding` element of a `wsdl:binding`.
+#[derive(Debug)]
+pub struct SoapBinding {
+    pub version: SoapVersion,
+    pub style: Option<SoapStyle>,
+    pub transport: String,
+}
+
+/// `soap:operation` or `soap12:operation` element of a binding operation.
+#[derive(Debug)]
+pub struct SoapOperation {
+    pub soap_action: Option<String>,
+    pub soap_action_required: Option<bool>,
+    pub style: Option<SoapStyle>,
+}
+
+/// `soap:body` element of a binding input or output.
+#[derive(Debug)]
+pub struct SoapBody {
+    pub parts: Option<Vec<String>>,
+    pub use_: Option<SoapUse>,
+    pub encoding_style: Vec<String>,
+    pub namespace: Option<String>,
+}
+
+/// `soap:header` element of a binding input or output.
+#[derive(Debug)]
+pub struct SoapHeader {
+    pub message: OwnedName,
+    pub part: String,
+    pub use_: Option<SoapUse>,
+    pub encoding_style: Vec<String>,
+    pub namespace: Option<String>,
+    pub header_faults: Vec<SoapHeaderFault>,
+}
+
+/// `soap:headerfault` element nested in a `soap:header`.
+#[derive(Debug)]
+pub struct SoapHeaderFault {
+    pub message: OwnedName,
+    pub part: String,
+    pub use_: Option<SoapUse>,
+    pub encoding_style: Vec<String>,
+    pub namespace: Option<String>,
+}
+
+/// `soap:fault` element of a binding fault.
+#[derive(Debug)]
+pub struct SoapFault {
+    pub name: Option<String>,
+    pub use_: Option<SoapUse>,
+    pub encoding_style: Vec<String>,
+    pub namespace: Option<String>,
+}
+
+impl SoapBinding {
+    pub fn read(name: &OwnedName, attributes: &[OwnedAttribute]) -> Result<SoapBinding> {
+        Ok(SoapBinding {
+               version: soap_version(name).unwrap_or(SoapVersion::Soap11),
+               style: read_style(attributes, "soap:binding")?,
+               transport: find_attribute("transport", attributes)
+                   .ok_or_else(|| {
+                                   ErrorKind::MandatoryAttribute("transport".to_string(),
+                                                                 "soap:binding".to_string())
+                               })?,
+           })
+    }
+}
+
+impl SoapOperation {
+    pub fn read(attributes: &[OwnedAttribute]) -> Result<SoapOperation> {
+        Ok(SoapOperation {
+               soap_action: find_attribute("soapAction", attributes),
+               soap_action_required: read_boolean("soapActionRequired",
+                                                  attributes,
+                                                  "soap12:operation")?,
+               style: read_style(attributes, "soap:operation")?,
+           })
+    }
+}
+
+impl SoapBody {
+    pub fn read(attributes: &[OwnedAttribute]) -> Result<SoapBody> {
+        Ok(SoapBody {
+               parts: find_attribute("parts", attributes)
+                   .map(|parts| parts.split_whitespace().map(String::from).collect()),
+               use_: read_use(attributes, "soap:body")?,
+               encoding_style: read_encoding_style(attributes),
+               namespace: find_attribute("namespace", attributes),
+           })
+    }
+}
+
+impl SoapHeader {
+    pub fn read(attributes: &[OwnedAttribute], namespace: &Namespace) -> Result<SoapHeader> {
+        let (message, part) = read_header_part(attributes, namespace, "soap:header")?;
+
+        Ok(SoapHeader {
+               message,
+               part,
+               use_: read_use(attributes, "soap:header")?,
+               encoding_style: read_encoding_style(attributes),
+               namespace: find_attribute("namespace", attributes),
+               header_faults: Vec::new(),
+           })
+    }
+}
+
+impl SoapHeaderFault {
+    pub fn read(attributes: &[OwnedAttribute], namespace: &Namespace) -> Result<SoapHeaderFault> {
+        let (message, part) = read_header_part(attributes, namespace, "soap:headerfault")?;
+
+        Ok(SoapHeaderFault {
+               message,
+               part,
+               use_: read_use(attributes, "soap:headerfault")?,
+               encoding_style: read_encoding_style(attributes),
+               namespace: find_attribute("namespace", attributes),
+           })
+    }
+}
+
+impl SoapFault {
+    pub fn read(attributes: &[OwnedAttribute]) -> Result<SoapFault> {
+        Ok(SoapFault {
+               name: find_attribute("name", attributes),
+               use_: read_use(attributes, "soap:fault")?,
+               encoding_style: read_encoding_style(attributes),
+               namespace: find_attribute("namespace", attributes),
+           })
+    }
+}
+
+/// SOAP version of a binding extension element, `None` for other namespaces.
+pub fn soap_version(name: &OwnedName) -> Option<SoapVersion> {
+    match name.namespace.as_deref() {
+        Some(NS_WSDL_SOAP) => Some(SoapVersion::Soap11),
+        Some(NS_WSDL_SOAP12) => Some(SoapVersion::Soap12),
+        _ => None,
+    }
+}
+
+pub fn is_soap_element(name: &OwnedName, local_name: &str) -> bool {
+    soap_version(name).is_some() && name.local_name == local_name
+}
+
+fn read_boolean(attribute: &str,
+                attributes: &[OwnedAttribute],
+                element: &str)
+                -> Result<Option<bool>> {
+    match find_attribute(attribute, attributes) {
+        None => Ok(None),
+        Some(ref value) if value == "true" || value == "1" => Ok(Some(true)),
+        Some(ref value) if value == "false" || value == "0" => Ok(Some(false)),
+        Some(value) => {
+            Err(ErrorKind::InvalidAttributeValue(attribute.to_string(), element.to_string(), value)
+                    .into())
+        }
+    }
+}
+
+fn read_style(attributes: &[OwnedAttribute], element: &str) -> Result<Option<SoapStyle>> {
+    match find_attribute("style", attributes) {
+        None => Ok(None),
+        Some(ref style) if style == "rpc" => Ok(Some(SoapStyle::Rpc)),
+        Some(ref style) if style == "document" => Ok(Some(SoapStyle::Document)),
+        Some(style) => {
+            Err(ErrorKind::InvalidAttributeValue("style".to_string(), element.to_string(), style)
+                    .into())
+        }
+    }
+}
+
+fn read_use(attributes: &[OwnedAttribute], element: &str) -> Result<Option<SoapUse>> {
+    match find_attribute("use", attributes) {
+        None => Ok(None),
+        Some(ref value) if value == "literal" => Ok(Some(SoapUse::Literal)),
+        Some(ref value) if value == "encoded" => Ok(Some(SoapUse::Encoded)),
+        Some(value) => {
+            Err(ErrorKind::InvalidAttributeValue("use".to_string(), element.to_string(), value)
+                    .into())
+        }
+    }
+}
+
+fn read_encoding_style(attributes: &[OwnedAttribute]) -> Vec<String> {
+    find_attribute("encodingStyle", attributes)
+        .map(|styles| styles.split_whitespace().map(String::from).collect())
+        .unwrap_or_default()
+}
+
+fn read_header_part(attributes: &[OwnedAttribute],
+                    namespace: &Namespace,
+                    element: &str)
+                    -> Result<(OwnedName, String)> {
+    let message = find_attribute("message", attributes)
+        .ok_or_else(|| ErrorKind::MandatoryAttribute("message".to_string(), element.to_string()))?;
+    let part = find_attribute("part", attributes)
+        .ok_or_else(|| ErrorKind::MandatoryAttribute("part".to_string(), element.to_string()))?;
+
+    Ok((read_qname(&message, namespace)?, part))
+}
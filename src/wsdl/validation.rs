@@ -0,0 +1,989 @@
+use super::errors::*;
+use super::import::is_xsd;
+use super::schema_set::SchemaSet;
+use super::xsd::*;
+
+use std::cmp::Ordering;
+
+use xml::name::OwnedName;
+
+/// Derivations followed to find the white space handling of a type.
+const MAX_DERIVATION_DEPTH: usize = 64;
+
+/// Value space of the built-in type a simple type is derived from, which
+/// its length and range facets apply to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ValueSpace {
+    /// Length facets count the items of lists.
+    List,
+    Decimal,
+    Float,
+    /// Dates and times in the format of `moment_format`.
+    Moment(&'static str),
+    Duration,
+    HexBinary,
+    Base64Binary,
+    /// Strings and types whose value space is not known.
+    Other,
+}
+
+impl<'a> SchemaSet<'a> {
+    /// Checks a lexical value against a simple type of the schemas or a
+    /// built-in XML Schema type, e.g. before it is sent in a request.
+    /// Built-in types that values cannot be checked against, such as
+    /// `xs:NOTATION`, fail with `UnsupportedType`.
+    pub fn validate(&self, type_name: &OwnedName, value: &str) -> Result<()> {
+        self.check_named(type_name, value, "validated value", &mut Vec::new())
+    }
+
+    pub fn validate_simple_type(&self, simple_type: &XsdSimpleType, value: &str) -> Result<()> {
+        self.check(simple_type, value, &mut Vec::new())
+    }
+
+    /// Checks `value` against a named type, `referrer` names the type that
+    /// refers to it in errors.
+    fn check_named(&self,
+                   name: &OwnedName,
+                   value: &str,
+                   referrer: &str,
+                   chain: &mut Vec<OwnedName>)
+                   -> Result<()> {
+        if is_xsd(name) {
+            let value = normalize(value, builtin_white_space(&name.local_name));
+            return check_builtin(&name.local_name, &value);
+        }
+
+        if chain.contains(name) {
+            let mut names = chain.iter().map(|n| n.local_name.clone()).collect::<Vec<_>>();
+            names.push(name.local_name.clone());
+            bail!(ErrorKind::CircularReference("Simple type".to_string(), names));
+        }
+
+        let simple_type = self.find_simple_type(name)
+            .ok_or_else(|| {
+                            ErrorKind::UnresolvedReference("Simple type".to_string(),
+                                                           name.local_name.clone(),
+                                                           referrer.to_string())
+                        })?;
+
+        chain.push(name.clone());
+        let result = self.check(simple_type, value, chain);
+        chain.pop();
+        result
+    }
+
+    fn check_ref(&self,
+                 type_ref: &XsdTypeRef,
+                 value: &str,
+                 referrer: &str,
+                 chain: &mut Vec<OwnedName>)
+                 -> Result<()> {
+        match *type_ref {
+            XsdTypeRef::Named(ref name) => self.check_named(name, value, referrer, chain),
+            XsdTypeRef::Simple(ref simple_type) => self.check(simple_type, value, chain),
+            XsdTypeRef::Complex(_) => Err(invalid(value, "complex types have no simple value")),
+        }
+    }
+
+    fn check(&self,
+             simple_type: &XsdSimpleType,
+             value: &str,
+             chain: &mut Vec<OwnedName>)
+             -> Result<()> {
+        let (white_space, space) = self.kind(simple_type, MAX_DERIVATION_DEPTH);
+        let value = normalize(value, white_space.unwrap_or(XsdWhiteSpace::Preserve));
+        // Anonymous types are named after the type they are defined in.
+        let referrer = simple_type
+            .name
+            .clone()
+            .or_else(|| chain.last().map(|n| n.local_name.clone()))
+            .unwrap_or_else(|| "anonymous type".to_string());
+
+        match simple_type.variety {
+            XsdSimpleVariety::Restriction { ref base, ref facets } => {
+                self.check_ref(base, &value, &referrer, chain)?;
+                self.check_facets(facets, &value, space)
+            }
+            XsdSimpleVariety::List { ref item_type } => {
+                for item in value.split_whitespace() {
+                    self.check_ref(item_type, item, &referrer, chain)?;
+                }
+                Ok(())
+            }
+            XsdSimpleVariety::Union { ref member_types } => {
+                if member_types
+                       .iter()
+                       .any(|member| self.check_ref(member, &value, &referrer, chain).is_ok()) {
+                    return Ok(());
+                }
+                Err(invalid(&value, "not valid for any member type of the union"))
+            }
+        }
+    }
+
+    /// White space handling of a type and the value space its facets apply to.
+    fn kind(&self,
+            simple_type: &XsdSimpleType,
+            depth: usize)
+            -> (Option<XsdWhiteSpace>, ValueSpace) {
+        let (base, facets) = match simple_type.variety {
+            XsdSimpleVariety::Restriction { ref base, ref facets } => (base, facets),
+            XsdSimpleVariety::List { .. } => {
+                return (Some(XsdWhiteSpace::Collapse), ValueSpace::List)
+            }
+            XsdSimpleVariety::Union { .. } => return (None, ValueSpace::Other),
+        };
+
+        let own = facets
+            .iter()
+            .filter_map(|facet| match *facet {
+                            XsdFacet::WhiteSpace(white_space) => Some(white_space),
+                            _ => None,
+                        })
+            .next();
+        let (inherited, space) = match *base {
+            _ if depth == 0 => (None, ValueSpace::Other),
+            XsdTypeRef::Named(ref name) if is_xsd(name) => {
+                (Some(builtin_white_space(&name.local_name)), value_space(&name.local_name))
+            }
+            XsdTypeRef::Named(ref name) => {
+                self.find_simple_type(name)
+                    .map(|base| self.kind(base, depth - 1))
+                    .unwrap_or((None, ValueSpace::Other))
+            }
+            XsdTypeRef::Simple(ref base) => self.kind(base, depth - 1),
+            XsdTypeRef::Complex(_) => (None, ValueSpace::Other),
+        };
+
+        (own.or(inherited), space)
+    }
+
+    fn check_facets(&self, facets: &[XsdFacet], value: &str, space: ValueSpace) -> Result<()> {
+        // Binary values are measured in octets.
+        let length = match space {
+            ValueSpace::List => value.split_whitespace().count(),
+            ValueSpace::HexBinary => value.len() / 2,
+            ValueSpace::Base64Binary => base64_octets(value).unwrap_or(0),
+            _ => value.chars().count(),
+        };
+        let holds = |bound: &str, allowed: &[Ordering]| match compare(space, value, bound) {
+            Some(ordering) => allowed.contains(&ordering),
+            // Dates and durations are partially ordered, some are incomparable.
+            None => false,
+        };
+
+        let enumeration = facets
+            .iter()
+            .filter_map(|facet| match *facet {
+                            XsdFacet::Enumeration(ref allowed) => Some(allowed.as_str()),
+                            _ => None,
+                        })
+            .collect::<Vec<_>>();
+        if !enumeration.is_empty() && !enumeration.contains(&value) {
+            return Err(invalid(value, "not one of the enumerated values"));
+        }
+
+        // Patterns of one restriction are alternatives.
+        let patterns = facets
+            .iter()
+            .filter_map(|facet| match *facet {
+                            XsdFacet::Pattern(ref pattern) => Some(pattern.as_str()),
+                            _ => None,
+                        })
+            .collect::<Vec<_>>();
+        if !patterns.is_empty() {
+            let mut matched = false;
+            for pattern in &patterns {
+                matched |= self.is_match(pattern, value)?;
+            }
+            if !matched {
+                return Err(invalid(value,
+                                   &format!("does not match pattern `{}`", patterns.join("|"))));
+            }
+        }
+
+        for facet in facets {
+            let violation = match *facet {
+                XsdFacet::Length(expected) if length != expected => {
+                    format!("length must be {}", expected)
+                }
+                XsdFacet::MinLength(min) if length < min => {
+                    format!("length must be at least {}", min)
+                }
+                XsdFacet::MaxLength(max) if length > max => {
+                    format!("length must be at most {}", max)
+                }
+                XsdFacet::MinInclusive(ref min)
+                    if !holds(min, &[Ordering::Greater, Ordering::Equal]) => {
+                    format!("must be at least {}", min)
+                }
+                XsdFacet::MaxInclusive(ref max)
+                    if !holds(max, &[Ordering::Less, Ordering::Equal]) => {
+                    format!("must be at most {}", max)
+                }
+                XsdFacet::MinExclusive(ref min) if !holds(min, &[Ordering::Greater]) => {
+                    format!("must be greater than {}", min)
+                }
+                XsdFacet::MaxExclusive(ref max) if !holds(max, &[Ordering::Less]) => {
+                    format!("must be less than {}", max)
+                }
+                XsdFacet::TotalDigits(max) if digits(value).0 > max => {
+                    format!("must have at most {} digits", max)
+                }
+                XsdFacet::FractionDigits(max) if digits(value).1 > max => {
+                    format!("must have at most {} fraction digits", max)
+                }
+                _ => continue,
+            };
+            return Err(invalid(value, &violation));
+        }
+
+        Ok(())
+    }
+}
+
+fn invalid(value: &str, reason: &str) -> Error {
+    ErrorKind::InvalidValue(value.to_string(), reason.to_string()).into()
+}
+
+fn normalize(value: &str, white_space: XsdWhiteSpace) -> String {
+    match white_space {
+        XsdWhiteSpace::Preserve => value.to_string(),
+        XsdWhiteSpace::Replace => value.replace(&['\t', '\n', '\r'][..], " "),
+        XsdWhiteSpace::Collapse => value.split_whitespace().collect::<Vec<_>>().join(" "),
+    }
+}
+
+fn builtin_white_space(name: &str) -> XsdWhiteSpace {
+    match name {
+        "string" | "anySimpleType" => XsdWhiteSpace::Preserve,
+        "normalizedString" => XsdWhiteSpace::Replace,
+        _ => XsdWhiteSpace::Collapse,
+    }
+}
+
+/// Value space of a built-in type, see `ValueSpace`.
+fn value_space(name: &str) -> ValueSpace {
+    match name {
+        "decimal" => ValueSpace::Decimal,
+        "float" | "double" => ValueSpace::Float,
+        "duration" => ValueSpace::Duration,
+        "hexBinary" => ValueSpace::HexBinary,
+        "base64Binary" => ValueSpace::Base64Binary,
+        _ if integer_bounds(name).is_some() => ValueSpace::Decimal,
+        _ => {
+            match moment_format(name) {
+                Some(format) => ValueSpace::Moment(format),
+                None => ValueSpace::Other,
+            }
+        }
+    }
+}
+
+/// Compares a value with the bound of a range facet, `None` when they are
+/// not comparable. Values of unknown types are compared as decimals if they
+/// are ones, by their lexical form otherwise.
+fn compare(space: ValueSpace, value: &str, bound: &str) -> Option<Ordering> {
+    match space {
+        ValueSpace::Decimal => compare_decimals(value, bound),
+        ValueSpace::Float => value.parse::<f64>().ok()?.partial_cmp(&bound.parse::<f64>().ok()?),
+        ValueSpace::Moment(format) => {
+            compare_moments(&parse_moment(format, value)?, &parse_moment(format, bound)?)
+        }
+        ValueSpace::Duration => compare_durations(&parse_duration(value)?, &parse_duration(bound)?),
+        _ => compare_decimals(value, bound).or_else(|| Some(value.cmp(bound))),
+    }
+}
+
+/// Sign, integer and fraction digits of a decimal, leading and trailing
+/// zeros are not significant and zero has no sign.
+fn decimal(value: &str) -> (bool, &str, &str) {
+    let unsigned = value.trim_start_matches(&['+', '-'][..]);
+    let (integer, fraction) = match unsigned.find('.') {
+        Some(dot) => (&unsigned[..dot], unsigned[dot + 1..].trim_end_matches('0')),
+        None => (unsigned, ""),
+    };
+    let integer = integer.trim_start_matches('0');
+    let negative = value.starts_with('-') && !(integer.is_empty() && fraction.is_empty());
+
+    (negative, integer, fraction)
+}
+
+/// Compares decimals exactly, by their digits.
+fn compare_decimals(value: &str, bound: &str) -> Option<Ordering> {
+    if !is_decimal(value) || !is_decimal(bound) {
+        return None;
+    }
+
+    let (value_negative, value_integer, value_fraction) = decimal(value);
+    let (bound_negative, bound_integer, bound_fraction) = decimal(bound);
+    if value_negative != bound_negative {
+        return Some(if value_negative {
+                        Ordering::Less
+                    } else {
+                        Ordering::Greater
+                    });
+    }
+
+    let magnitude = value_integer
+        .len()
+        .cmp(&bound_integer.len())
+        .then(value_integer.cmp(bound_integer))
+        .then(value_fraction.cmp(bound_fraction));
+    Some(if value_negative {
+             magnitude.reverse()
+         } else {
+             magnitude
+         })
+}
+
+/// Total and fraction digits of a decimal.
+fn digits(value: &str) -> (usize, usize) {
+    let (_, integer, fraction) = decimal(value);
+    (integer.len() + fraction.len(), fraction.len())
+}
+
+/// A point on the time line, in seconds since the epoch and the digits of
+/// the fraction of a second. Times with a timezone are normalized to UTC.
+#[derive(Debug, Clone, PartialEq)]
+struct Moment {
+    seconds: i128,
+    fraction: String,
+    has_timezone: bool,
+}
+
+/// Fields of the date and time types in the order they are written, `Y`,
+/// `M` and `D` for the date, `h`, `m` and `s` for the time. Other characters
+/// are written as they are.
+fn moment_format(name: &str) -> Option<&'static str> {
+    let format = match name {
+        "dateTime" => "Y-M-DTh:m:s",
+        "date" => "Y-M-D",
+        "time" => "h:m:s",
+        "gYearMonth" => "Y-M",
+        "gYear" => "Y",
+        "gMonthDay" => "--M-D",
+        "gDay" => "---D",
+        "gMonth" => "--M",
+        _ => return None,
+    };
+
+    Some(format)
+}
+
+/// Parses a date or time in the given format, followed by an optional
+/// timezone. Missing date fields are taken from the leap year 1972.
+fn parse_moment(format: &str, value: &str) -> Option<Moment> {
+    if !value.is_ascii() {
+        return None;
+    }
+
+    let (mut year, mut month, mut day) = (1972, 1, 1);
+    let (mut hour, mut minute, mut second) = (0, 0, 0);
+    let mut fraction = String::new();
+    let mut position = 0;
+    for field in format.chars() {
+        match field {
+            'Y' => {
+                let negative = value[position..].starts_with('-');
+                if negative {
+                    position += 1;
+                }
+                let start = position;
+                year = number(value, &mut position, 4, 30)?;
+                // Longer years have no leading zeros and there is no year zero.
+                if (position - start > 4 && value[start..].starts_with('0')) || year == 0 {
+                    return None;
+                }
+                if negative {
+                    year = -year;
+                }
+            }
+            'M' => month = number(value, &mut position, 2, 2)?,
+            'D' => day = number(value, &mut position, 2, 2)?,
+            'h' => hour = number(value, &mut position, 2, 2)?,
+            'm' => minute = number(value, &mut position, 2, 2)?,
+            's' => {
+                second = number(value, &mut position, 2, 2)?;
+                if value[position..].starts_with('.') {
+                    position += 1;
+                    fraction = fraction_digits(value, &mut position)?;
+                }
+            }
+            literal => {
+                if !value[position..].starts_with(literal) {
+                    return None;
+                }
+                position += 1;
+            }
+        }
+    }
+    let timezone = parse_timezone(&value[position..])?;
+
+    // Midnight may be written as 24:00:00 of the day before.
+    let midnight = hour == 24 && minute == 0 && second == 0 && fraction.is_empty();
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) ||
+       (hour > 23 && !midnight) || minute > 59 || second > 59 {
+        return None;
+    }
+
+    Some(Moment {
+             seconds: days_from_civil(year, month, day) * 86_400 + hour * 3_600 +
+                      minute * 60 + second - timezone.unwrap_or(0) * 60,
+             fraction,
+             has_timezone: timezone.is_some(),
+         })
+}
+
+/// Reads an unsigned number of `min` to `max` digits.
+fn number(value: &str, position: &mut usize, min: usize, max: usize) -> Option<i128> {
+    let count = value[*position..]
+        .bytes()
+        .take(max)
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+    if count < min {
+        return None;
+    }
+
+    let number = value[*position..*position + count].parse().ok()?;
+    *position += count;
+    Some(number)
+}
+
+/// Reads the digits of a fraction, without trailing zeros.
+fn fraction_digits(value: &str, position: &mut usize) -> Option<String> {
+    let count = value[*position..].bytes().take_while(|b| b.is_ascii_digit()).count();
+    if count == 0 {
+        return None;
+    }
+
+    let digits = value[*position..*position + count].trim_end_matches('0').to_string();
+    *position += count;
+    Some(digits)
+}
+
+/// Offset of a timezone in minutes, `Some(None)` when there is none.
+fn parse_timezone(value: &str) -> Option<Option<i128>> {
+    let sign = match value.chars().next() {
+        None => return Some(None),
+        Some('Z') if value.len() == 1 => return Some(Some(0)),
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return None,
+    };
+
+    let mut position = 1;
+    let hours = number(value, &mut position, 2, 2)?;
+    if !value[position..].starts_with(':') {
+        return None;
+    }
+    position += 1;
+    let minutes = number(value, &mut position, 2, 2)?;
+    if position != value.len() || minutes > 59 || hours > 14 || (hours == 14 && minutes > 0) {
+        return None;
+    }
+
+    Some(Some(sign * (hours * 60 + minutes)))
+}
+
+fn days_in_month(year: i128, month: i128) -> i128 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i128, month: i128, day: i128) -> i128 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Moments with and without a timezone are only ordered when they are
+/// more than the largest offset of 14 hours apart.
+fn compare_moments(value: &Moment, bound: &Moment) -> Option<Ordering> {
+    let at = |moment: &Moment, offset: i128| (moment.seconds + offset, moment.fraction.clone());
+    let (value_offset, bound_offset) = match (value.has_timezone, bound.has_timezone) {
+        (true, false) => (0, 50_400),
+        (false, true) => (50_400, 0),
+        _ => (0, 0),
+    };
+    let plus = at(value, -value_offset).cmp(&at(bound, bound_offset));
+    let minus = at(value, value_offset).cmp(&at(bound, -bound_offset));
+
+    if plus == minus {
+        Some(plus)
+    } else {
+        None
+    }
+}
+
+/// Months and seconds of a duration, with the digits of the fraction of a
+/// second.
+#[derive(Debug, Clone, PartialEq)]
+struct Duration {
+    negative: bool,
+    months: i128,
+    seconds: i128,
+    fraction: String,
+}
+
+fn parse_duration(value: &str) -> Option<Duration> {
+    if !value.is_ascii() {
+        return None;
+    }
+
+    let negative = value.starts_with('-');
+    let value = value.trim_start_matches('-');
+    if !value.starts_with('P') {
+        return None;
+    }
+    let (date, time) = match value.find('T') {
+        Some(t) => (&value[1..t], Some(&value[t + 1..])),
+        None => (&value[1..], None),
+    };
+
+    let mut fields = duration_fields(date, "YMD")?;
+    if let Some(time) = time {
+        let time_fields = duration_fields(time, "HMS")?;
+        if time_fields.is_empty() {
+            return None;
+        }
+        // Minutes of the time are told apart from months of the date.
+        fields.extend(time_fields.into_iter().map(|(field, number, fraction)| {
+            (field.to_ascii_lowercase(), number, fraction)
+        }));
+    }
+    if fields.is_empty() {
+        return None;
+    }
+
+    let mut duration = Duration {
+        negative,
+        months: 0,
+        seconds: 0,
+        fraction: String::new(),
+    };
+    for (field, number, fraction) in fields {
+        match field {
+            'Y' => duration.months += number * 12,
+            'M' => duration.months += number,
+            'D' => duration.seconds += number * 86_400,
+            'h' => duration.seconds += number * 3_600,
+            'm' => duration.seconds += number * 60,
+            _ => {
+                duration.seconds += number;
+                duration.fraction = fraction;
+            }
+        }
+    }
+
+    Some(duration)
+}
+
+/// Numbers of a part of a duration followed by their designators, which
+/// must be in order. Only seconds may have a fraction.
+fn duration_fields(value: &str, designators: &str) -> Option<Vec<(char, i128, String)>> {
+    let mut fields = Vec::new();
+    let mut remaining = designators;
+    let mut position = 0;
+
+    while position < value.len() {
+        let amount = number(value, &mut position, 1, 30)?;
+        let mut fraction = String::new();
+        if value[position..].starts_with('.') {
+            position += 1;
+            fraction = fraction_digits(value, &mut position)?;
+        }
+
+        let designator = value[position..].chars().next()?;
+        let index = remaining.find(designator)?;
+        if designator != 'S' && !fraction.is_empty() {
+            return None;
+        }
+        remaining = &remaining[index + 1..];
+        position += 1;
+        fields.push((designator, amount, fraction));
+    }
+
+    Some(fields)
+}
+
+/// Durations are ordered when adding them to each of four dates orders the
+/// results the same way.
+fn compare_durations(value: &Duration, bound: &Duration) -> Option<Ordering> {
+    let starts = [(1696, 9), (1697, 2), (1903, 3), (1903, 7)];
+    let orderings = starts
+        .iter()
+        .map(|&start| add_duration(start, value).cmp(&add_duration(start, bound)))
+        .collect::<Vec<_>>();
+
+    if orderings.iter().all(|&o| o == orderings[0]) {
+        Some(orderings[0])
+    } else {
+        None
+    }
+}
+
+/// Seconds and fraction digits of the first day of a month plus a duration.
+fn add_duration((year, month): (i128, i128), duration: &Duration) -> (i128, String) {
+    let sign = if duration.negative { -1 } else { 1 };
+    let months = year * 12 + month - 1 + sign * duration.months;
+    let start = days_from_civil(months.div_euclid(12), months.rem_euclid(12) + 1, 1) * 86_400;
+
+    if duration.negative && !duration.fraction.is_empty() {
+        // Fractions are positive, subtracting one borrows a second.
+        (start - duration.seconds - 1, complement(&duration.fraction))
+    } else {
+        (start + sign * duration.seconds, duration.fraction.clone())
+    }
+}
+
+/// Digits of one minus a fraction, whose last digit is not zero.
+fn complement(fraction: &str) -> String {
+    let last = fraction.len() - 1;
+    fraction
+        .bytes()
+        .enumerate()
+        .map(|(i, digit)| {
+                 let nines = if i == last { b'9' + 1 } else { b'9' };
+                 (nines - digit + b'0') as char
+             })
+        .collect()
+}
+
+fn check_builtin(name: &str, value: &str) -> Result<()> {
+    let valid = match name {
+        "anyType" | "anySimpleType" | "string" | "normalizedString" | "token" => true,
+        "boolean" => ["true", "false", "1", "0"].contains(&value),
+        "decimal" => is_decimal(value),
+        "float" | "double" => {
+            ["INF", "-INF", "NaN"].contains(&value) ||
+            (is_decimal(value.split(&['e', 'E'][..]).next().unwrap_or("")) &&
+             value.parse::<f64>().is_ok())
+        }
+        "duration" => parse_duration(value).is_some(),
+        "hexBinary" => {
+            value
+                .as_bytes()
+                .chunks(2)
+                .all(|octet| octet.len() == 2 && octet.iter().all(|b| b.is_ascii_hexdigit()))
+        }
+        "base64Binary" => base64_octets(value).is_some(),
+        "anyURI" => is_uri(value),
+        "language" => is_language(value),
+        "Name" => is_name(value),
+        "NCName" | "ID" | "IDREF" | "ENTITY" => is_ncname(value),
+        "QName" => value.splitn(2, ':').all(is_ncname),
+        "NMTOKEN" => is_nmtoken(value),
+        "NMTOKENS" => !value.is_empty() && value.split(' ').all(is_nmtoken),
+        "IDREFS" | "ENTITIES" => !value.is_empty() && value.split(' ').all(is_ncname),
+        _ => {
+            match (integer_bounds(name), moment_format(name)) {
+                (Some((min, max)), _) => is_integer(value) && in_bounds(value, min, max),
+                (None, Some(format)) => parse_moment(format, value).is_some(),
+                (None, None) => bail!(ErrorKind::UnsupportedType(name.to_string())),
+            }
+        }
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(invalid(value, &format!("not a valid xs:{}", name)))
+    }
+}
+
+fn is_decimal(value: &str) -> bool {
+    let unsigned = value.trim_start_matches(&['+', '-'][..]);
+    unsigned.len() + 1 >= value.len() && unsigned.chars().any(|c| c.is_ascii_digit()) &&
+    unsigned.chars().all(|c| c.is_ascii_digit() || c == '.') &&
+    unsigned.matches('.').count() <= 1
+}
+
+fn is_integer(value: &str) -> bool {
+    is_decimal(value) && !value.contains('.')
+}
+
+fn in_bounds(value: &str, min: Option<i128>, max: Option<i128>) -> bool {
+    match value.parse::<i128>() {
+        Ok(value) => min.map_or(true, |min| value >= min) && max.map_or(true, |max| value <= max),
+        // Only integers beyond any fixed bound overflow.
+        Err(_) if value.starts_with('-') => min.is_none(),
+        Err(_) => max.is_none(),
+    }
+}
+
+/// Value range of the built-in integer types.
+fn integer_bounds(name: &str) -> Option<(Option<i128>, Option<i128>)> {
+    let bounds = match name {
+        "integer" => (None, None),
+        "nonPositiveInteger" => (None, Some(0)),
+        "negativeInteger" => (None, Some(-1)),
+        "nonNegativeInteger" => (Some(0), None),
+        "positiveInteger" => (Some(1), None),
+        "long" => (Some(i64::MIN as i128), Some(i64::MAX as i128)),
+        "int" => (Some(i32::MIN as i128), Some(i32::MAX as i128)),
+        "short" => (Some(i16::MIN as i128), Some(i16::MAX as i128)),
+        "byte" => (Some(i8::MIN as i128), Some(i8::MAX as i128)),
+        "unsignedLong" => (Some(0), Some(u64::MAX as i128)),
+        "unsignedInt" => (Some(0), Some(u32::MAX as i128)),
+        "unsignedShort" => (Some(0), Some(u16::MAX as i128)),
+        "unsignedByte" => (Some(0), Some(u8::MAX as i128)),
+        _ => return None,
+    };
+
+    Some(bounds)
+}
+
+/// Octets of base64 encoded data, `None` when it is not valid.
+fn base64_octets(value: &str) -> Option<usize> {
+    let encoded = value.bytes().filter(|&b| b != b' ').collect::<Vec<_>>();
+    let padding = encoded.iter().rev().take_while(|&&b| b == b'=').count();
+    let data = &encoded[..encoded.len() - padding];
+
+    if encoded.len() % 4 != 0 || padding > 2 ||
+       !data.iter().all(|&b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/') {
+        return None;
+    }
+    Some(encoded.len() / 4 * 3 - padding)
+}
+
+/// `anyURI` allows any string that is a URI reference once disallowed
+/// characters are escaped, so only escapes, the fragment and the scheme
+/// are checked.
+fn is_uri(value: &str) -> bool {
+    let escapes = value
+        .match_indices('%')
+        .all(|(i, _)| {
+                 let digits = value[i + 1..].bytes().take(2).filter(|b| b.is_ascii_hexdigit());
+                 digits.count() == 2
+             });
+    let scheme = match value.find(&[':', '/', '?', '#'][..]) {
+        Some(end) if value[end..].starts_with(':') => {
+            let scheme = &value[..end];
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic()) &&
+            scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        }
+        _ => true,
+    };
+
+    escapes && scheme && value.matches('#').count() <= 1
+}
+
+fn is_language(value: &str) -> bool {
+    value.split('-').enumerate().all(|(i, tag)| {
+        (1..=8).contains(&tag.len()) &&
+        tag.chars().all(|c| c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()))
+    })
+}
+
+/// Names use the characters of the `\i` and `\c` pattern escapes.
+fn is_name(value: &str) -> bool {
+    value.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == ':') && is_nmtoken(value)
+}
+
+fn is_ncname(value: &str) -> bool {
+    is_name(value) && !value.contains(':')
+}
+
+fn is_nmtoken(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_alphanumeric() || ".-_:".contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::file::example;
+    use super::super::element::XmlElement;
+    use super::super::import::NS_XSD;
+    use super::super::schema::Wsdl;
+
+    fn reason(result: Result<()>) -> String {
+        match result {
+            Err(Error(ErrorKind::InvalidValue(_, reason), _)) => reason,
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn validates_enumerations_of_examples_test() {
+        let wsdl = Wsdl::load_from_file(&example("travel_light/service.wsdl")).unwrap();
+        let schemas = wsdl.schema_set();
+        let language = OwnedName::qualified("LanguageCode", "ElsyArres.API", None::<&str>);
+
+        assert!(schemas.validate(&language, "EN").is_ok());
+        assert_eq!("not one of the enumerated values",
+                   reason(schemas.validate(&language, "XY")));
+    }
+
+    #[test]
+    fn validates_facets_test() {
+        let schema = XmlElement::parse(br#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           xmlns:tns="urn:test" targetNamespace="urn:test">
+    <xs:simpleType name="Month">
+        <xs:restriction base="xs:int">
+            <xs:minInclusive value="1"/>
+            <xs:maxInclusive value="12"/>
+        </xs:restriction>
+    </xs:simpleType>
+    <xs:simpleType name="Carrier">
+        <xs:restriction base="xs:token">
+            <xs:length value="2"/>
+            <xs:pattern value="[A-Z0-9]+"/>
+        </xs:restriction>
+    </xs:simpleType>
+    <xs:simpleType name="Carriers">
+        <xs:restriction>
+            <xs:simpleType><xs:list itemType="tns:Carrier"/></xs:simpleType>
+            <xs:maxLength value="3"/>
+        </xs:restriction>
+    </xs:simpleType>
+    <xs:simpleType name="Amount">
+        <xs:union memberTypes="tns:Month">
+            <xs:simpleType>
+                <xs:restriction base="xs:decimal"><xs:fractionDigits value="2"/></xs:restriction>
+            </xs:simpleType>
+        </xs:union>
+    </xs:simpleType>
+    <xs:simpleType name="Codes">
+        <xs:list><xs:simpleType><xs:restriction base="tns:Missing"/></xs:simpleType></xs:list>
+    </xs:simpleType>
+</xs:schema>"#)
+                .unwrap();
+        let schema = XsdSchema::read(&schema).unwrap();
+        let schemas = SchemaSet::new(vec![&schema]);
+        let name = |local: &str| OwnedName::qualified(local, "urn:test", None::<&str>);
+
+        assert!(schemas.validate(&name("Month"), " 12 ").is_ok());
+        assert_eq!("must be at most 12", reason(schemas.validate(&name("Month"), "13")));
+        assert_eq!("not a valid xs:int", reason(schemas.validate(&name("Month"), "May")));
+
+        assert!(schemas.validate(&name("Carrier"), "BA").is_ok());
+        assert_eq!("does not match pattern `[A-Z0-9]+`",
+                   reason(schemas.validate(&name("Carrier"), "ba")));
+        assert!(schemas.validate(&name("Carriers"), "BA  LH\tU2").is_ok());
+        assert_eq!("length must be at most 3",
+                   reason(schemas.validate(&name("Carriers"), "BA LH U2 AF")));
+        assert_eq!("length must be 2", reason(schemas.validate(&name("Carriers"), "BAW")));
+
+        assert!(schemas.validate(&name("Amount"), "12.50").is_ok());
+        assert!(schemas.validate(&name("Amount"), "12.505").is_err());
+
+        match schemas.validate(&name("Codes"), "A1") {
+            Err(Error(ErrorKind::UnresolvedReference(_, ref name, ref referrer), _)) => {
+                assert_eq!(("Missing", "Codes"), (name.as_str(), referrer.as_str()))
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+
+        let int = OwnedName::qualified("unsignedShort", NS_XSD, None::<&str>);
+        assert!(schemas.validate(&int, "65535").is_ok());
+        assert!(schemas.validate(&int, "-1").is_err());
+    }
+
+    #[test]
+    fn validates_lexical_spaces_of_builtin_types_test() {
+        let schemas = SchemaSet::new(Vec::new());
+        let valid = |name: &str, value: &str| {
+            schemas
+                .validate(&OwnedName::qualified(name, NS_XSD, None::<&str>), value)
+                .is_ok()
+        };
+
+        assert!(!valid("dateTime", "2017-02-28T23:59:60.5Z"));
+        assert!(valid("dateTime", "2016-02-29T24:00:00+14:00"));
+        assert!(!valid("dateTime", "2017-02-29T10:00:00"));
+        assert!(!valid("dateTime", "2017-02-28"));
+        assert!(valid("date", "-0044-03-15"));
+        assert!(!valid("date", "0000-01-01"));
+        assert!(valid("time", "13:20:00.000-05:00"));
+        assert!(!valid("time", "24:00:01"));
+        assert!(valid("gYear", "12017Z") && !valid("gYear", "017"));
+        assert!(valid("gYearMonth", "2017-12") && !valid("gYearMonth", "2017-13"));
+        assert!(valid("gMonthDay", "--02-29") && !valid("gMonthDay", "--04-31"));
+        assert!(valid("gDay", "---31") && valid("gMonth", "--12+01:00"));
+
+        assert!(valid("duration", "-P1Y2M3DT4H5M6.7S") && valid("duration", "PT0S"));
+        assert!(!valid("duration", "P") && !valid("duration", "P1DT"));
+        assert!(!valid("duration", "P1M1Y") && !valid("duration", "P1.5D"));
+
+        assert!(valid("hexBinary", "0FB7") && !valid("hexBinary", "0FB"));
+        assert!(valid("base64Binary", "SGVsbG8=") && valid("base64Binary", "SGVs bG8h"));
+        assert!(!valid("base64Binary", "SGVsbG8") && !valid("base64Binary", "S=Vsbbb="));
+        assert!(valid("anyURI", "http://example.com/a%20b#top") && valid("anyURI", "../a.xsd"));
+        assert!(!valid("anyURI", "http://example.com/%2") && !valid("anyURI", "1a:b"));
+
+        assert!(valid("language", "en-GB") && !valid("language", "en_GB"));
+        assert!(valid("QName", "xs:string") && !valid("QName", "xs:"));
+        assert!(valid("NMTOKENS", "a-1 .b") && !valid("NCName", "1a"));
+
+        match schemas.validate(&OwnedName::qualified("NOTATION", NS_XSD, None::<&str>), "a") {
+            Err(Error(ErrorKind::UnsupportedType(ref name), _)) => assert_eq!("NOTATION", name),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn compares_bounds_in_value_spaces_test() {
+        let schema = XmlElement::parse(br#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+           targetNamespace="urn:test">
+    <xs:simpleType name="Price">
+        <xs:restriction base="xs:decimal">
+            <xs:maxInclusive value="9007199254740993.1"/>
+        </xs:restriction>
+    </xs:simpleType>
+    <xs:simpleType name="Departure">
+        <xs:restriction base="xs:dateTime">
+            <xs:minExclusive value="2017-06-30T23:00:00Z"/>
+        </xs:restriction>
+    </xs:simpleType>
+    <xs:simpleType name="Stay">
+        <xs:restriction base="xs:duration">
+            <xs:maxInclusive value="P30D"/>
+        </xs:restriction>
+    </xs:simpleType>
+    <xs:simpleType name="Checksum">
+        <xs:restriction base="xs:hexBinary"><xs:length value="2"/></xs:restriction>
+    </xs:simpleType>
+    <xs:simpleType name="Key">
+        <xs:restriction base="xs:base64Binary"><xs:maxLength value="5"/></xs:restriction>
+    </xs:simpleType>
+</xs:schema>"#)
+                .unwrap();
+        let schema = XsdSchema::read(&schema).unwrap();
+        let schemas = SchemaSet::new(vec![&schema]);
+        let name = |local: &str| OwnedName::qualified(local, "urn:test", None::<&str>);
+
+        // Both are the same `f64`.
+        assert!(schemas.validate(&name("Price"), "9007199254740993.1").is_ok());
+        assert_eq!("must be at most 9007199254740993.1",
+                   reason(schemas.validate(&name("Price"), "9007199254740993.10001")));
+        assert!(schemas.validate(&name("Price"), "-9007199254740994").is_ok());
+
+        assert!(schemas.validate(&name("Departure"), "2017-07-01T01:00:00+01:00").is_ok());
+        assert!(schemas.validate(&name("Departure"), "2017-07-01T00:00:00+01:00").is_err());
+        // Without a timezone, values within 14 hours of the bound are not comparable.
+        assert!(schemas.validate(&name("Departure"), "2017-07-01T10:00:00").is_err());
+        assert!(schemas.validate(&name("Departure"), "2017-07-01T14:00:01").is_ok());
+
+        assert!(schemas.validate(&name("Stay"), "P31D").is_err());
+        assert!(schemas.validate(&name("Stay"), "PT720H").is_ok());
+        assert!(schemas.validate(&name("Stay"), "-P1M").is_ok());
+        // A month is 28 to 31 days long.
+        assert!(schemas.validate(&name("Stay"), "P1M").is_err());
+
+        assert!(schemas.validate(&name("Checksum"), "0FB7").is_ok());
+        assert_eq!("length must be 2", reason(schemas.validate(&name("Checksum"), "0F")));
+        assert!(schemas.validate(&name("Key"), "SGVsbG8=").is_ok());
+        assert!(schemas.validate(&name("Key"), "SGVsbG8h").is_err());
+    }
+}
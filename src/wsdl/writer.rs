@@ -0,0 +1,869 @@
+use super::element::{XmlElement, XmlNode};
+use super::errors::*;
+use super::schema::*;
+use super::soap::*;
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use xml::attribute::OwnedAttribute;
+use xml::escape::{escape_str_attribute, escape_str_pcdata};
+use xml::name::OwnedName;
+use xml::namespace::{NS_XML_PREFIX, NS_XML_URI};
+
+impl Wsdl {
+    /// Serializes the definitions as a WSDL 1.1 document. Namespace prefixes
+    /// of the parsed document are kept. Components merged from imported
+    /// documents are not written, the `wsdl:import` elements refer to them.
+    pub fn to_xml(&self) -> String {
+        let mut writer = Writer::new(self);
+        writer.definitions(self);
+        writer.out
+    }
+
+    pub fn write<W: Write>(&self, out: &mut W) -> Result<()> {
+        out.write_all(self.to_xml().as_bytes())?;
+        Ok(())
+    }
+}
+
+struct Writer {
+    out: String,
+    depth: usize,
+    /// Namespace declarations of the root element, by prefix.
+    namespaces: BTreeMap<String, String>,
+    /// SOAP version of the binding being written.
+    soap_version: SoapVersion,
+}
+
+impl Writer {
+    fn new(wsdl: &Wsdl) -> Writer {
+        let mut writer = Writer {
+            out: String::new(),
+            depth: 0,
+            namespaces: wsdl.namespaces.clone(),
+            soap_version: SoapVersion::Soap11,
+        };
+
+        writer.declare(NS_WSDL, "wsdl", true);
+        if let Some(ref target_namespace) = wsdl.target_namespace {
+            writer.declare(target_namespace, "tns", false);
+        }
+
+        writer.declare_attributes(&wsdl.extension_attributes);
+        for import in wsdl.imports.iter().filter(|i| i.imported_from.is_none()) {
+            writer.declare_attributes(&import.extension_attributes);
+        }
+
+        for binding in wsdl.bindings.iter().filter(|b| b.imported_from.is_none()) {
+            writer.declare_binding(binding);
+        }
+        for port in wsdl.services
+                .iter()
+                .filter(|s| s.imported_from.is_none())
+                .flat_map(|s| s.ports.iter()) {
+            writer.declare_qname(&port.binding);
+            writer.declare_attributes(&port.extension_attributes);
+            if let Some(ref address) = port.address {
+                writer.declare(address_namespace(address.protocol),
+                               address_prefix(address.protocol),
+                               true);
+            }
+        }
+        for service in wsdl.services.iter().filter(|s| s.imported_from.is_none()) {
+            writer.declare_attributes(&service.extension_attributes);
+        }
+        for port_type in wsdl.port_types.iter().filter(|p| p.imported_from.is_none()) {
+            writer.declare_attributes(&port_type.extension_attributes);
+            for operation in &port_type.operations {
+                writer.declare_attributes(&operation.extension_attributes);
+                let messages = operation
+                    .input
+                    .iter()
+                    .chain(operation.output.iter())
+                    .chain(operation.faults.iter());
+                for message in messages {
+                    writer.declare_attributes(&message.extension_attributes);
+                    if let Some(ref name) = message.message {
+                        writer.declare_qname(name);
+                    }
+                }
+            }
+        }
+        for message in wsdl.messages.iter().filter(|m| m.imported_from.is_none()) {
+            writer.declare_attributes(&message.extension_attributes);
+            for part in &message.parts {
+                writer.declare_attributes(&part.extension_attributes);
+                for name in part.element.iter().chain(part.part_type.iter()) {
+                    writer.declare_qname(name);
+                }
+            }
+        }
+
+        writer
+    }
+
+    fn declare_binding(&mut self, binding: &WsdlBinding) {
+        self.declare_qname(&binding.port_type);
+        if let Some(version) = binding.soap_version() {
+            self.declare(soap_namespace(version), soap_prefix(version), true);
+        }
+        if binding.http_binding.is_some() ||
+           binding.operations.iter().any(|o| o.http_operation.is_some()) {
+            self.declare(NS_WSDL_HTTP, "http", true);
+        }
+
+        self.declare_attributes(&binding.extension_attributes);
+        for operation in &binding.operations {
+            self.declare_attributes(&operation.extension_attributes);
+            if let Some(ref input) = operation.input {
+                self.declare_attributes(&input.extension_attributes);
+                self.declare_headers(&input.soap_headers);
+            }
+            if let Some(ref output) = operation.output {
+                self.declare_attributes(&output.extension_attributes);
+                self.declare_headers(&output.soap_headers);
+            }
+            for fault in &operation.faults {
+                self.declare_attributes(&fault.extension_attributes);
+            }
+        }
+    }
+
+    fn declare_attributes(&mut self, attributes: &[OwnedAttribute]) {
+        for attribute in attributes {
+            self.declare_qname(&attribute.name);
+        }
+    }
+
+    fn declare_headers(&mut self, headers: &[SoapHeader]) {
+        for header in headers {
+            self.declare_qname(&header.message);
+            for header_fault in &header.header_faults {
+                self.declare_qname(&header_fault.message);
+            }
+        }
+    }
+
+    fn declare_qname(&mut self, name: &OwnedName) {
+        if let Some(ref namespace) = name.namespace {
+            let prefix = name.prefix.clone().unwrap_or_else(|| "ns".to_string());
+            self.declare(namespace, &prefix, false);
+        }
+    }
+
+    /// Declares a prefix for `namespace` on the root element unless there is
+    /// one already. Element names may use the default namespace, QNames in
+    /// attribute values may not.
+    fn declare(&mut self, namespace: &str, preferred: &str, allow_default: bool) {
+        if namespace == NS_XML_URI || self.prefix(namespace, allow_default).is_some() {
+            return;
+        }
+
+        let mut prefix = preferred.to_string();
+        let mut suffix = 1;
+        while self.namespaces.contains_key(&prefix) {
+            prefix = format!("{}{}", preferred, suffix);
+            suffix += 1;
+        }
+
+        self.namespaces.insert(prefix, namespace.to_string());
+    }
+
+    fn prefix(&self, namespace: &str, allow_default: bool) -> Option<&str> {
+        if namespace == NS_XML_URI {
+            return Some(NS_XML_PREFIX);
+        }
+
+        let mut default = None;
+
+        for (prefix, uri) in &self.namespaces {
+            if uri == namespace {
+                if !prefix.is_empty() {
+                    return Some(prefix);
+                }
+                if allow_default {
+                    default = Some(prefix.as_str());
+                }
+            }
+        }
+
+        default
+    }
+
+    fn name(&self, namespace: &str, local_name: &str) -> String {
+        match self.prefix(namespace, true) {
+            Some(prefix) if !prefix.is_empty() => format!("{}:{}", prefix, local_name),
+            _ => local_name.to_string(),
+        }
+    }
+
+    fn wsdl(&self, local_name: &str) -> String {
+        self.name(NS_WSDL, local_name)
+    }
+
+    fn qname(&self, name: &OwnedName) -> String {
+        let namespace = match name.namespace {
+            Some(ref namespace) => namespace,
+            None => return name.local_name.clone(),
+        };
+
+        let prefix = match name.prefix {
+            Some(ref prefix) if self.namespaces.get(prefix) == Some(namespace) => prefix.as_str(),
+            _ => self.prefix(namespace, false).unwrap_or(""),
+        };
+
+        format!("{}:{}", prefix, name.local_name)
+    }
+
+    fn definitions(&mut self, wsdl: &Wsdl) {
+        self.out.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+
+        let mut attributes = self.namespaces
+            .iter()
+            .map(|(prefix, uri)| if prefix.is_empty() {
+                     ("xmlns".to_string(), uri.clone())
+                 } else {
+                     (format!("xmlns:{}", prefix), uri.clone())
+                 })
+            .collect::<Vec<_>>();
+        push(&mut attributes, "name", &wsdl.name);
+        push(&mut attributes, "targetNamespace", &wsdl.target_namespace);
+        self.push_extensions(&mut attributes, &wsdl.extension_attributes);
+
+        let definitions = self.wsdl("definitions");
+        self.start(&definitions, &attributes);
+        self.documentation(&wsdl.documentation);
+        self.extensions(&wsdl.extensions);
+
+        for import in wsdl.imports.iter().filter(|i| i.imported_from.is_none()) {
+            let mut attributes = vec![("namespace".to_string(), import.namespace.clone()),
+                                      ("location".to_string(), import.location.clone())];
+            self.push_extensions(&mut attributes, &import.extension_attributes);
+            self.documented(&self.wsdl("import"),
+                            &attributes,
+                            &import.documentation,
+                            &import.extensions);
+        }
+        for types in wsdl.types.iter().filter(|t| t.imported_from.is_none()) {
+            self.types(types);
+        }
+        for message in wsdl.messages.iter().filter(|m| m.imported_from.is_none()) {
+            self.message(message);
+        }
+        for port_type in wsdl.port_types.iter().filter(|p| p.imported_from.is_none()) {
+            self.port_type(port_type);
+        }
+        for binding in wsdl.bindings.iter().filter(|b| b.imported_from.is_none()) {
+            self.binding(binding);
+        }
+        for service in wsdl.services.iter().filter(|s| s.imported_from.is_none()) {
+            self.service(service);
+        }
+
+        self.end(&definitions);
+    }
+
+    fn types(&mut self, types: &WsdlTypes) {
+        let name = self.wsdl("types");
+        self.start(&name, &[]);
+        self.documentation(&types.documentation);
+        for schema in &types.schemas {
+            self.element(schema);
+        }
+        self.end(&name);
+    }
+
+    fn message(&mut self, message: &WsdlMessage) {
+        let name = self.wsdl("message");
+        let mut attributes = vec![("name".to_string(), message.name.clone())];
+        self.push_extensions(&mut attributes, &message.extension_attributes);
+        self.start(&name, &attributes);
+        self.documentation(&message.documentation);
+        self.extensions(&message.extensions);
+
+        for part in &message.parts {
+            let mut attributes = vec![("name".to_string(), part.name.clone())];
+            if let Some(ref element) = part.element {
+                attributes.push(("element".to_string(), self.qname(element)));
+            }
+            if let Some(ref part_type) = part.part_type {
+                attributes.push(("type".to_string(), self.qname(part_type)));
+            }
+            self.push_extensions(&mut attributes, &part.extension_attributes);
+            self.documented(&self.wsdl("part"),
+                            &attributes,
+                            &part.documentation,
+                            &part.extensions);
+        }
+
+        self.end(&name);
+    }
+
+    fn port_type(&mut self, port_type: &WsdlPortType) {
+        let name = self.wsdl("portType");
+        let mut attributes = vec![("name".to_string(), port_type.name.clone())];
+        self.push_extensions(&mut attributes, &port_type.extension_attributes);
+        self.start(&name, &attributes);
+        self.documentation(&port_type.documentation);
+        self.extensions(&port_type.extensions);
+
+        for operation in &port_type.operations {
+            let mut attributes = vec![("name".to_string(), operation.name.clone())];
+            if !operation.parameter_order.is_empty() {
+                attributes.push(("parameterOrder".to_string(), operation.parameter_order.join(" ")));
+            }
+            self.push_extensions(&mut attributes, &operation.extension_attributes);
+
+            let operation_name = self.wsdl("operation");
+            self.start(&operation_name, &attributes);
+            self.documentation(&operation.documentation);
+            self.extensions(&operation.extensions);
+            if let Some(ref input) = operation.input {
+                self.operation_message("input", input);
+            }
+            if let Some(ref output) = operation.output {
+                self.operation_message("output", output);
+            }
+            for fault in &operation.faults {
+                self.operation_message("fault", fault);
+            }
+            self.end(&operation_name);
+        }
+
+        self.end(&name);
+    }
+
+    fn operation_message(&mut self, local_name: &str, message: &WsdlOperationMessage) {
+        let mut attributes = Vec::new();
+        push(&mut attributes, "name", &message.name);
+        if let Some(ref reference) = message.message {
+            attributes.push(("message".to_string(), self.qname(reference)));
+        }
+        self.push_extensions(&mut attributes, &message.extension_attributes);
+
+        self.documented(&self.wsdl(local_name),
+                        &attributes,
+                        &message.documentation,
+                        &message.extensions);
+    }
+
+    fn binding(&mut self, binding: &WsdlBinding) {
+        let name = self.wsdl("binding");
+        let version = binding.soap_version().unwrap_or(SoapVersion::Soap11);
+        self.soap_version = version;
+        let mut attributes = vec![("name".to_string(), binding.name.clone()),
+                                  ("type".to_string(), self.qname(&binding.port_type))];
+        self.push_extensions(&mut attributes, &binding.extension_attributes);
+        self.start(&name, &attributes);
+        self.documentation(&binding.documentation);
+
+        if let Some(ref soap_binding) = binding.soap_binding {
+            let mut attributes = Vec::new();
+            if let Some(style) = soap_binding.style {
+                attributes.push(("style".to_string(), style_value(style).to_string()));
+            }
+            attributes.push(("transport".to_string(), soap_binding.transport.clone()));
+            self.empty(&self.name(soap_namespace(version), "binding"), &attributes);
+        }
+        if let Some(ref http_binding) = binding.http_binding {
+            let mut attributes = Vec::new();
+            push(&mut attributes, "verb", &http_binding.method);
+            self.empty(&self.name(NS_WSDL_HTTP, "binding"), &attributes);
+        }
+        self.extensions(&binding.extensions);
+
+        for operation in &binding.operations {
+            self.operation_binding(operation);
+        }
+
+        self.end(&name);
+    }
+
+    fn operation_binding(&mut self, operation: &WsdlOperationBinding) {
+        let name = self.wsdl("operation");
+        let version = self.soap_version;
+        let mut attributes = vec![("name".to_string(), operation.name.clone())];
+        self.push_extensions(&mut attributes, &operation.extension_attributes);
+        self.start(&name, &attributes);
+        self.documentation(&operation.documentation);
+
+        if let Some(ref soap_operation) = operation.soap_operation {
+            let mut attributes = Vec::new();
+            push(&mut attributes, "soapAction", &soap_operation.soap_action);
+            if let Some(required) = soap_operation.soap_action_required {
+                attributes.push(("soapActionRequired".to_string(), required.to_string()));
+            }
+            if let Some(style) = soap_operation.style {
+                attributes.push(("style".to_string(), style_value(style).to_string()));
+            }
+            self.empty(&self.name(soap_namespace(version), "operation"), &attributes);
+        }
+        if let Some(ref http_operation) = operation.http_operation {
+            let mut attributes = Vec::new();
+            push(&mut attributes, "location", &http_operation.location);
+            self.empty(&self.name(NS_WSDL_HTTP, "operation"), &attributes);
+        }
+        self.extensions(&operation.extensions);
+
+        if let Some(ref input) = operation.input {
+            let mut attributes = Vec::new();
+            push(&mut attributes, "name", &input.name);
+            self.push_extensions(&mut attributes, &input.extension_attributes);
+            self.message_binding("input",
+                                 &attributes,
+                                 &input.documentation,
+                                 &input.soap_body,
+                                 &input.soap_headers,
+                                 &input.extensions);
+        }
+        if let Some(ref output) = operation.output {
+            let mut attributes = Vec::new();
+            push(&mut attributes, "name", &output.name);
+            self.push_extensions(&mut attributes, &output.extension_attributes);
+            self.message_binding("output",
+                                 &attributes,
+                                 &output.documentation,
+                                 &output.soap_body,
+                                 &output.soap_headers,
+                                 &output.extensions);
+        }
+        for fault in &operation.faults {
+            let fault_name = self.wsdl("fault");
+            let mut attributes = vec![("name".to_string(), fault.name.clone())];
+            self.push_extensions(&mut attributes, &fault.extension_attributes);
+            self.start(&fault_name, &attributes);
+            self.documentation(&fault.documentation);
+            if let Some(ref soap_fault) = fault.soap_fault {
+                let mut attributes = Vec::new();
+                push(&mut attributes, "name", &soap_fault.name);
+                push_encoding(&mut attributes,
+                              soap_fault.use_,
+                              &soap_fault.encoding_style,
+                              &soap_fault.namespace);
+                self.empty(&self.name(soap_namespace(version), "fault"), &attributes);
+            }
+            self.extensions(&fault.extensions);
+            self.end(&fault_name);
+        }
+
+        self.end(&name);
+    }
+
+    fn message_binding(&mut self,
+                       local_name: &str,
+                       attributes: &[(String, String)],
+                       documentation: &Option<WsdlDocumentation>,
+                       soap_body: &Option<SoapBody>,
+                       soap_headers: &[SoapHeader],
+                       extensions: &[WsdlExtension]) {
+        let name = self.wsdl(local_name);
+        let version = self.soap_version;
+        self.start(&name, attributes);
+        self.documentation(documentation);
+
+        if let Some(ref soap_body) = *soap_body {
+            let mut attributes = Vec::new();
+            if let Some(ref parts) = soap_body.parts {
+                attributes.push(("parts".to_string(), parts.join(" ")));
+            }
+            push_encoding(&mut attributes,
+                          soap_body.use_,
+                          &soap_body.encoding_style,
+                          &soap_body.namespace);
+            self.empty(&self.name(soap_namespace(version), "body"), &attributes);
+        }
+
+        for header in soap_headers {
+            let header_name = self.name(soap_namespace(version), "header");
+            let mut attributes = vec![("message".to_string(), self.qname(&header.message)),
+                                      ("part".to_string(), header.part.clone())];
+            push_encoding(&mut attributes,
+                          header.use_,
+                          &header.encoding_style,
+                          &header.namespace);
+
+            if header.header_faults.is_empty() {
+                self.empty(&header_name, &attributes);
+                continue;
+            }
+
+            self.start(&header_name, &attributes);
+            for header_fault in &header.header_faults {
+                let mut attributes =
+                    vec![("message".to_string(), self.qname(&header_fault.message)),
+                         ("part".to_string(), header_fault.part.clone())];
+                push_encoding(&mut attributes,
+                              header_fault.use_,
+                              &header_fault.encoding_style,
+                              &header_fault.namespace);
+                self.empty(&self.name(soap_namespace(version), "headerfault"), &attributes);
+            }
+            self.end(&header_name);
+        }
+
+        self.extensions(extensions);
+        self.end(&name);
+    }
+
+    fn service(&mut self, service: &WsdlService) {
+        let name = self.wsdl("service");
+        let mut attributes = vec![("name".to_string(), service.name.clone())];
+        self.push_extensions(&mut attributes, &service.extension_attributes);
+        self.start(&name, &attributes);
+        self.documentation(&service.documentation);
+        self.extensions(&service.extensions);
+
+        for port in &service.ports {
+            let port_name = self.wsdl("port");
+            let mut attributes = vec![("name".to_string(), port.name.clone()),
+                                      ("binding".to_string(), self.qname(&port.binding))];
+            self.push_extensions(&mut attributes, &port.extension_attributes);
+            self.start(&port_name, &attributes);
+            self.documentation(&port.documentation);
+            if let Some(ref address) = port.address {
+                self.empty(&self.name(address_namespace(address.protocol), "address"),
+                           &[("location".to_string(), address.location.clone())]);
+            }
+            self.extensions(&port.extensions);
+            self.end(&port_name);
+        }
+
+        self.end(&name);
+    }
+
+    fn extensions(&mut self, extensions: &[WsdlExtension]) {
+        for extension in extensions {
+            self.element(extension);
+        }
+    }
+
+    /// Adds extension attributes, their namespaces are declared on the root element.
+    fn push_extensions(&self,
+                       attributes: &mut Vec<(String, String)>,
+                       extension_attributes: &[OwnedAttribute]) {
+        attributes.extend(extension_attributes
+                              .iter()
+                              .map(|a| (self.qname(&a.name), a.value.clone())));
+    }
+
+    fn documentation(&mut self, documentation: &Option<WsdlDocumentation>) {
+        if let Some(ref documentation) = *documentation {
+            let content = match documentation.markup {
+                Some(ref markup) => markup.clone(),
+                None => escape_str_pcdata(&documentation.text).into_owned(),
+            };
+            let name = self.wsdl("documentation");
+            self.line(&format!("<{}>{}</{}>", name, content, name));
+        }
+    }
+
+    /// Element whose only possible children are its documentation and extensions.
+    fn documented(&mut self,
+                  name: &str,
+                  attributes: &[(String, String)],
+                  documentation: &Option<WsdlDocumentation>,
+                  extensions: &[WsdlExtension]) {
+        if documentation.is_none() && extensions.is_empty() {
+            self.empty(name, attributes);
+            return;
+        }
+
+        self.start(name, attributes);
+        self.documentation(documentation);
+        self.extensions(extensions);
+        self.end(name);
+    }
+
+    /// Writes a kept element, declaring the namespaces the root element does not.
+    fn element(&mut self, element: &XmlElement) {
+        let namespaces = element
+            .namespaces
+            .iter()
+            .filter(|&(prefix, uri)| self.namespaces.get(prefix) != Some(uri))
+            .map(|(prefix, uri)| (prefix.clone(), uri.clone()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut out = String::new();
+        write_element(element, &namespaces, self.depth, &mut out);
+        self.out.push_str(&out);
+    }
+
+    fn start(&mut self, name: &str, attributes: &[(String, String)]) {
+        let tag = format!("<{}{}>", name, format_attributes(attributes));
+        self.line(&tag);
+        self.depth += 1;
+    }
+
+    fn empty(&mut self, name: &str, attributes: &[(String, String)]) {
+        let tag = format!("<{}{}/>", name, format_attributes(attributes));
+        self.line(&tag);
+    }
+
+    fn end(&mut self, name: &str) {
+        self.depth -= 1;
+        self.line(&format!("</{}>", name));
+    }
+
+    fn line(&mut self, content: &str) {
+        for _ in 0..self.depth {
+            self.out.push_str("  ");
+        }
+        self.out.push_str(content);
+        self.out.push('\n');
+    }
+}
+
+/// Writes an element on its own lines, or on a single line when it has text.
+fn write_element(element: &XmlElement,
+                 namespaces: &BTreeMap<String, String>,
+                 depth: usize,
+                 out: &mut String) {
+    let indent = "  ".repeat(depth);
+    let has_text = element
+        .children
+        .iter()
+        .any(|c| match *c {
+                 XmlNode::Text(_) => true,
+                 XmlNode::Element(_) => false,
+             });
+
+    out.push_str(&indent);
+    if has_text {
+        write_inline(element, namespaces, out);
+        out.push('\n');
+        return;
+    }
+
+    let name = element.name.borrow().to_repr();
+    out.push_str(&start_tag(element, namespaces));
+    if element.children.is_empty() {
+        out.insert(out.len() - 1, '/');
+        out.push('\n');
+        return;
+    }
+
+    out.push('\n');
+    for child in element.elements() {
+        write_element(child, &child.namespaces, depth + 1, out);
+    }
+    out.push_str(&format!("{}</{}>\n", indent, name));
+}
+
+fn write_inline(element: &XmlElement, namespaces: &BTreeMap<String, String>, out: &mut String) {
+    let name = element.name.borrow().to_repr();
+    out.push_str(&start_tag(element, namespaces));
+    if element.children.is_empty() {
+        out.insert(out.len() - 1, '/');
+        return;
+    }
+
+    for child in &element.children {
+        match *child {
+            XmlNode::Element(ref child) => write_inline(child, &child.namespaces, out),
+            XmlNode::Text(ref text) => out.push_str(&escape_str_pcdata(text)),
+        }
+    }
+    out.push_str(&format!("</{}>", name));
+}
+
+fn start_tag(element: &XmlElement, namespaces: &BTreeMap<String, String>) -> String {
+    let mut attributes = namespaces
+        .iter()
+        .map(|(prefix, uri)| if prefix.is_empty() {
+                 ("xmlns".to_string(), uri.clone())
+             } else {
+                 (format!("xmlns:{}", prefix), uri.clone())
+             })
+        .collect::<Vec<_>>();
+    attributes.extend(element
+                          .attributes
+                          .iter()
+                          .map(|a| (a.name.borrow().to_repr(), a.value.clone())));
+
+    format!("<{}{}>",
+            element.name.borrow().to_repr(),
+            format_attributes(&attributes))
+}
+
+fn format_attributes(attributes: &[(String, String)]) -> String {
+    attributes
+        .iter()
+        .map(|(name, value)| format!(" {}=\"{}\"", name, escape_str_attribute(value)))
+        .collect()
+}
+
+fn push(attributes: &mut Vec<(String, String)>, name: &str, value: &Option<String>) {
+    if let Some(ref value) = *value {
+        attributes.push((name.to_string(), value.clone()));
+    }
+}
+
+fn push_encoding(attributes: &mut Vec<(String, String)>,
+                 use_: Option<SoapUse>,
+                 encoding_style: &[String],
+                 namespace: &Option<String>) {
+    if let Some(use_) = use_ {
+        let value = match use_ {
+            SoapUse::Literal => "literal",
+            SoapUse::Encoded => "encoded",
+        };
+        attributes.push(("use".to_string(), value.to_string()));
+    }
+    if !encoding_style.is_empty() {
+        attributes.push(("encodingStyle".to_string(), encoding_style.join(" ")));
+    }
+    push(attributes, "namespace", namespace);
+}
+
+fn style_value(style: SoapStyle) -> &'static str {
+    match style {
+        SoapStyle::Rpc => "rpc",
+        SoapStyle::Document => "document",
+    }
+}
+
+fn soap_namespace(version: SoapVersion) -> &'static str {
+    match version {
+        SoapVersion::Soap11 => NS_WSDL_SOAP,
+        SoapVersion::Soap12 => NS_WSDL_SOAP12,
+    }
+}
+
+fn soap_prefix(version: SoapVersion) -> &'static str {
+    match version {
+        SoapVersion::Soap11 => "soap",
+        SoapVersion::Soap12 => "soap12",
+    }
+}
+
+fn address_namespace(protocol: AddressProtocol) -> &'static str {
+    match protocol {
+        AddressProtocol::Soap11 => NS_WSDL_SOAP,
+        AddressProtocol::Soap12 => NS_WSDL_SOAP12,
+        AddressProtocol::Http => NS_WSDL_HTTP,
+    }
+}
+
+fn address_prefix(protocol: AddressProtocol) -> &'static str {
+    match protocol {
+        AddressProtocol::Soap11 => "soap",
+        AddressProtocol::Soap12 => "soap12",
+        AddressProtocol::Http => "http",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::file::example;
+    use super::super::resolver::MemoryResolver;
+
+    fn assert_round_trip(location: &str) -> String {
+        let wsdl = Wsdl::load_from_file(&example(location)).unwrap();
+        let written = wsdl.to_xml();
+        let reread = Wsdl::parse(written.as_bytes()).unwrap();
+
+        assert_eq!(format!("{:#?}", wsdl), format!("{:#?}", reread));
+        written
+    }
+
+    #[test]
+    fn round_trips_hello_test() {
+        let written = assert_round_trip("hello_world/hello.wsdl");
+
+        assert!(written.contains("<definitions xmlns=\"http://schemas.xmlsoap.org/wsdl/\""));
+        assert!(written.contains("<soap:binding style=\"rpc\""));
+        assert!(written.contains("<message name=\"SayHelloRequest\">"));
+    }
+
+    #[test]
+    fn round_trips_etoimik_test() {
+        let written = assert_round_trip("weather/etoimik.wsdl");
+
+        assert!(written.contains("xmlns:xrd=\"http://x-road.ee/xsd/x-road.xsd\""));
+        assert!(written.contains("<xsd:schema xmlns:ns1="));
+    }
+
+    #[test]
+    fn declares_missing_prefixes_test() {
+        let mut wsdl = Wsdl::parse(r#"<?xml version="1.0" encoding="utf-8"?>
+<definitions xmlns="http://schemas.xmlsoap.org/wsdl/" targetNamespace="urn:test"/>
+"#
+                                           .as_bytes())
+                .unwrap();
+        wsdl.services.push(WsdlService {
+                               documentation: None,
+                               target_namespace: Some("urn:test".to_string()),
+                               imported_from: None,
+                               name: "Service".to_string(),
+                               extensions: Vec::new(),
+                               extension_attributes: Vec::new(),
+                               ports: vec![WsdlPort {
+                                               documentation: None,
+                                               name: "Port".to_string(),
+                                               binding: OwnedName::qualified("Binding",
+                                                                             "urn:test",
+                                                                             None::<&str>),
+                                               address: Some(WsdlAddress {
+                                                                 protocol: AddressProtocol::Soap12,
+                                                                 location: "http://example.com"
+                                                                     .to_string(),
+                                                             }),
+                                               extensions: Vec::new(),
+                                               extension_attributes: Vec::new(),
+                                           }],
+                           });
+
+        let written = wsdl.to_xml();
+
+        assert!(written.contains("xmlns:soap12=\"http://schemas.xmlsoap.org/wsdl/soap12/\""));
+        assert!(written.contains("xmlns:tns=\"urn:test\""));
+        assert!(written.contains("<port name=\"Port\" binding=\"tns:Binding\">"));
+        assert!(written.contains("<soap12:address location=\"http://example.com\"/>"));
+    }
+
+    #[test]
+    fn round_trips_imports_of_same_namespace_test() {
+        let mut resolver = MemoryResolver::new();
+        resolver
+            .insert("http://example.com/service.wsdl",
+                    r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/"
+                  xmlns:tns="urn:air" targetNamespace="urn:air">
+    <wsdl:import namespace="urn:air" location="abstract.wsdl"/>
+    <wsdl:binding name="Binding" type="tns:PortType"/>
+    <wsdl:service name="Service">
+        <wsdl:port name="Port" binding="tns:Binding"/>
+    </wsdl:service>
+</wsdl:definitions>"#)
+            .unwrap();
+        resolver
+            .insert("http://example.com/abstract.wsdl",
+                    r#"<?xml version="1.0" encoding="utf-8"?>
+<wsdl:definitions xmlns:wsdl="http://schemas.xmlsoap.org/wsdl/"
+                  xmlns:tns="urn:air" targetNamespace="urn:air">
+    <wsdl:message name="Request"/>
+    <wsdl:portType name="PortType">
+        <wsdl:operation name="Search">
+            <wsdl:input message="tns:Request"/>
+        </wsdl:operation>
+    </wsdl:portType>
+</wsdl:definitions>"#)
+            .unwrap();
+
+        let wsdl = Wsdl::load_with_resolver("http://example.com/service.wsdl", &resolver)
+            .unwrap();
+        let written = wsdl.to_xml();
+        assert!(!written.contains("Request"));
+        assert!(!written.contains("<portType"));
+
+        resolver.insert("http://example.com/written.wsdl", written).unwrap();
+        let reread = Wsdl::load_with_resolver("http://example.com/written.wsdl", &resolver)
+            .unwrap();
+
+        assert_eq!(1, reread.messages.len());
+        assert_eq!(1, reread.port_types.len());
+        assert_eq!(format!("{:#?}", wsdl), format!("{:#?}", reread));
+    }
+}
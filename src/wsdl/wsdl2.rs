@@ -0,0 +1,909 @@
+use super::element::{declared_namespaces, XmlElement};
+use super::errors::*;
+use super::reader::Reader;
+use super::schema::{self, *};
+use super::soap::*;
+
+use xml::attribute::OwnedAttribute;
+use xml::name::OwnedName;
+use xml::namespace::Namespace;
+use xml::reader::XmlEvent;
+
+pub const NS_WSDL2: &str = "http://www.w3.org/ns/wsdl";
+pub const NS_WSDL2_SOAP: &str = "http://www.w3.org/ns/wsdl/soap";
+pub const NS_WSDL2_HTTP: &str = "http://www.w3.org/ns/wsdl/http";
+
+pub fn is_description(name: &OwnedName) -> bool {
+    is_wsdl2_element(name, "description")
+}
+
+/// Reads a WSDL 2.0 `description` into the WSDL 1.1 shaped model: interfaces
+/// become port types and endpoints become ports. Operation inputs, outputs
+/// and faults refer to schema elements directly, each of them gets a message
+/// with a single `parameters` part for its element so that the model can be
+/// written as WSDL 1.1.
+pub fn read_description(attributes: &[OwnedAttribute],
+                        namespace: &Namespace,
+                        iter: &mut Reader)
+                        -> Result<Wsdl> {
+    let target_namespace = find_attribute("targetNamespace", attributes);
+
+    let mut depth = 0;
+    let mut documentation = None;
+    let mut imports = Vec::new();
+    let mut types = Vec::new();
+    let mut port_types = Vec::new();
+    let mut services = Vec::new();
+    let mut bindings = Vec::new();
+    let mut extensions = Vec::new();
+
+    while let Some(v) = iter.next() {
+        match (v?, depth) {
+            (XmlEvent::StartElement { ref name, .. }, 0) if is_wsdl2_element(name,
+                                                                             "documentation") => {
+                documentation = Some(WsdlDocumentation::read(iter)?)
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ..
+             },
+             0) if is_wsdl2_element(name, "import") || is_wsdl2_element(name, "include") => {
+                let namespace = find_attribute("namespace", attributes)
+                    .or_else(|| target_namespace.clone());
+                let location = find_attribute("location", attributes);
+                let (import_documentation, import_extensions) = read_documented(iter)?;
+
+                // Imports without a location only declare a namespace dependency.
+                if let (Some(namespace), Some(location)) = (namespace, location) {
+                    imports.push(WsdlImport {
+                                     documentation: import_documentation,
+                                     namespace,
+                                     location,
+                                     imported_from: None,
+                                     extensions: import_extensions,
+                                     extension_attributes: extension_attributes(attributes),
+                                 });
+                }
+            }
+            (XmlEvent::StartElement { ref name, .. }, 0) if is_wsdl2_element(name, "types") => {
+                types.push(WsdlTypes::read(iter)?)
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if is_wsdl2_element(name, "interface") => {
+                port_types.extend(iter.component(|iter| {
+                                                     read_interface(attributes,
+                                                                    namespace,
+                                                                    &target_namespace,
+                                                                    iter)
+                                                 })?)
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if is_wsdl2_element(name, "binding") => {
+                bindings.extend(iter.component(|iter| read_binding(attributes, namespace, iter))?)
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ..
+             },
+             0) if is_wsdl2_element(name, "service") => {
+                services.extend(iter.component(|iter| read_service(attributes, iter))?)
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if !is_wsdl2(name) => {
+                extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
+            }
+            (XmlEvent::StartElement { .. }, _) => depth += 1,
+            (XmlEvent::EndElement { .. }, 0) => break,
+            (XmlEvent::EndElement { .. }, _) => depth -= 1,
+            _ => continue,
+        }
+    }
+
+    let messages = synthesize_messages(&mut port_types, &target_namespace);
+    let mut wsdl = Wsdl {
+        version: WsdlVersion::Wsdl20,
+        documentation,
+        name: None,
+        target_namespace,
+        namespaces: declared_namespaces(namespace, None),
+        imports,
+        types,
+        port_types,
+        services,
+        bindings,
+        messages,
+        extensions,
+        extension_attributes: extension_attributes(attributes),
+    };
+    wsdl.assign_target_namespace();
+    assign_address_protocols(&mut wsdl);
+
+    Ok(wsdl)
+}
+
+fn read_interface(attributes: &[OwnedAttribute],
+                  namespace: &Namespace,
+                  target_namespace: &Option<String>,
+                  iter: &mut Reader)
+                  -> Result<WsdlPortType> {
+    let name = find_attribute("name", attributes)
+        .ok_or_else(|| {
+                        ErrorKind::MandatoryAttribute("name".to_string(),
+                                                      "wsdl:interface".to_string())
+                    })?;
+    let extends = read_qname_list(find_attribute("extends", attributes), namespace)?;
+
+    let mut depth = 0;
+    let mut documentation = None;
+    let mut faults = Vec::new();
+    let mut operations = Vec::new();
+    let mut extensions = Vec::new();
+
+    while let Some(v) = iter.next() {
+        match (v?, depth) {
+            (XmlEvent::StartElement { ref name, .. }, 0) if is_wsdl2_element(name,
+                                                                             "documentation") => {
+                documentation = Some(WsdlDocumentation::read(iter)?)
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if is_wsdl2_element(name, "fault") => {
+                let fault_name = find_attribute("name", attributes)
+                    .ok_or_else(|| {
+                                    ErrorKind::MandatoryAttribute("name".to_string(),
+                                                                  "wsdl:fault".to_string())
+                                })?;
+                let element = read_element_reference(attributes, namespace)?;
+                skip_element(iter)?;
+                faults.push((fault_name, element));
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ..
+             },
+             0) if is_wsdl2_element(name, "operation") => {
+                operations.extend(iter.component(|iter| read_interface_operation(attributes, iter))?)
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if !is_wsdl2(name) => {
+                extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
+            }
+            (XmlEvent::StartElement { .. }, _) => depth += 1,
+            (XmlEvent::EndElement { .. }, 0) => break,
+            (XmlEvent::EndElement { .. }, _) => depth -= 1,
+            _ => continue,
+        }
+    }
+
+    // Operation faults reference the interface faults by QName, faults of
+    // interfaces in other namespaces are left without an element.
+    let mut port_type_operations = Vec::new();
+    for (mut operation, references) in operations {
+        for (fault, reference) in operation.faults.iter_mut().zip(references) {
+            fault.element = faults
+                .iter()
+                .find(|&(name, _)| {
+                          reference.namespace == *target_namespace &&
+                          reference.local_name == *name
+                      })
+                .and_then(|(_, element)| element.clone());
+        }
+        port_type_operations.push(operation);
+    }
+
+    Ok(WsdlPortType {
+           documentation,
+           target_namespace: None,
+           imported_from: None,
+           name,
+           extends,
+           operations: port_type_operations,
+           extensions,
+           extension_attributes: extension_attributes(attributes),
+       })
+}
+
+/// Reads an interface operation along with the QNames of the interface
+/// faults its faults reference.
+fn read_interface_operation(attributes: &[OwnedAttribute],
+                            iter: &mut Reader)
+                            -> Result<(WsdlOperation, Vec<OwnedName>)> {
+    let name = find_attribute("name", attributes)
+        .ok_or_else(|| {
+                        ErrorKind::MandatoryAttribute("name".to_string(),
+                                                      "wsdl:operation".to_string())
+                    })?;
+
+    let mut depth = 0;
+    let mut documentation = None;
+    let mut input = None;
+    let mut output = None;
+    let mut faults = Vec::new();
+    let mut references = Vec::new();
+    let mut extensions = Vec::new();
+
+    while let Some(v) = iter.next() {
+        match (v?, depth) {
+            (XmlEvent::StartElement { ref name, .. }, 0) if is_wsdl2_element(name,
+                                                                             "documentation") => {
+                documentation = Some(WsdlDocumentation::read(iter)?)
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if is_wsdl2_element(name, "input") || is_wsdl2_element(name, "output") => {
+                let element = read_element_reference(attributes, namespace)?;
+                let (documentation, extensions) = read_documented(iter)?;
+                let message = WsdlOperationMessage {
+                    name: find_attribute("messageLabel", attributes),
+                    message: None,
+                    element,
+                    documentation,
+                    extensions,
+                    extension_attributes: extension_attributes(attributes),
+                };
+
+                if name.local_name == "input" {
+                    input = Some(message);
+                } else {
+                    output = Some(message);
+                }
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if is_wsdl2_element(name, "infault") || is_wsdl2_element(name, "outfault") => {
+                let reference = find_attribute("ref", attributes)
+                    .ok_or_else(|| {
+                                    ErrorKind::MandatoryAttribute("ref".to_string(),
+                                                                  format!("wsdl:{}",
+                                                                          name.local_name))
+                                })?;
+                let reference = read_qname(&reference, namespace)?;
+                let (documentation, extensions) = read_documented(iter)?;
+
+                faults.push(WsdlOperationMessage {
+                                name: Some(reference.local_name.clone()),
+                                message: None,
+                                element: None,
+                                documentation,
+                                extensions,
+                                extension_attributes: extension_attributes(attributes),
+                            });
+                references.push(reference);
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if !is_wsdl2(name) => {
+                extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
+            }
+            (XmlEvent::StartElement { .. }, _) => depth += 1,
+            (XmlEvent::EndElement { .. }, 0) => break,
+            (XmlEvent::EndElement { .. }, _) => depth -= 1,
+            _ => continue,
+        }
+    }
+
+    let operation = WsdlOperation {
+        documentation,
+        name,
+        pattern: find_attribute("pattern", attributes),
+        parameter_order: Vec::new(),
+        input,
+        output,
+        faults,
+        extensions,
+        extension_attributes: extension_attributes(attributes),
+    };
+    Ok((operation, references))
+}
+
+fn read_binding(attributes: &[OwnedAttribute],
+                namespace: &Namespace,
+                iter: &mut Reader)
+                -> Result<WsdlBinding> {
+    let name = find_attribute("name", attributes)
+        .ok_or_else(|| {
+                        ErrorKind::MandatoryAttribute("name".to_string(),
+                                                      "wsdl:binding".to_string())
+                    })?;
+    let interface = find_attribute("interface", attributes)
+        .ok_or_else(|| {
+                        ErrorKind::MandatoryAttribute("interface".to_string(),
+                                                      "wsdl:binding".to_string())
+                    })?;
+    let binding_type = find_attribute("type", attributes)
+        .ok_or_else(|| {
+                        ErrorKind::MandatoryAttribute("type".to_string(),
+                                                      "wsdl:binding".to_string())
+                    })?;
+
+    let mut soap_binding = None;
+    let mut http_binding = None;
+
+    if binding_type == NS_WSDL2_SOAP {
+        let version = match find_ns_attribute(NS_WSDL2_SOAP, "version", attributes) {
+            Some(ref version) if version == "1.1" => SoapVersion::Soap11,
+            _ => SoapVersion::Soap12,
+        };
+
+        soap_binding = Some(SoapBinding {
+                                version,
+                                style: None,
+                                transport: find_ns_attribute(NS_WSDL2_SOAP,
+                                                             "protocol",
+                                                             attributes)
+                                        .unwrap_or_default(),
+                            });
+    } else if binding_type == NS_WSDL2_HTTP {
+        http_binding = Some(HttpBinding {
+                                method: find_ns_attribute(NS_WSDL2_HTTP,
+                                                          "methodDefault",
+                                                          attributes),
+                            });
+    }
+
+    let mut depth = 0;
+    let mut documentation = None;
+    let mut extensions = Vec::new();
+    let mut operations = Vec::new();
+
+    while let Some(v) = iter.next() {
+        match (v?, depth) {
+            (XmlEvent::StartElement { ref name, .. }, 0) if is_wsdl2_element(name,
+                                                                             "documentation") => {
+                documentation = Some(WsdlDocumentation::read(iter)?)
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if is_wsdl2_element(name, "operation") => {
+                operations.extend(iter.component(|iter| read_binding_operation(attributes, namespace, iter))?)
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if !is_wsdl2(name) => {
+                extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
+            }
+            (XmlEvent::StartElement { .. }, _) => depth += 1,
+            (XmlEvent::EndElement { .. }, 0) => break,
+            (XmlEvent::EndElement { .. }, _) => depth -= 1,
+            _ => continue,
+        }
+    }
+
+    Ok(WsdlBinding {
+           documentation,
+           target_namespace: None,
+           imported_from: None,
+           name,
+           port_type: read_qname(&interface, namespace)?,
+           soap_binding,
+           http_binding,
+           extensions,
+           extension_attributes: extension_attributes(attributes),
+           operations,
+       })
+}
+
+fn read_binding_operation(attributes: &[OwnedAttribute],
+                          namespace: &Namespace,
+                          iter: &mut Reader)
+                          -> Result<WsdlOperationBinding> {
+    let reference = find_attribute("ref", attributes)
+        .ok_or_else(|| {
+                        ErrorKind::MandatoryAttribute("ref".to_string(),
+                                                      "wsdl:operation".to_string())
+                    })?;
+    let reference = read_qname(&reference, namespace)?;
+
+    let soap_operation = find_ns_attribute(NS_WSDL2_SOAP, "action", attributes)
+        .map(|action| {
+                 SoapOperation {
+                     soap_action: Some(action),
+                     soap_action_required: None,
+                     style: None,
+                 }
+             });
+
+    let method = find_ns_attribute(NS_WSDL2_HTTP, "method", attributes);
+    let location = find_ns_attribute(NS_WSDL2_HTTP, "location", attributes);
+    let http_operation = if method.is_some() || location.is_some() {
+        Some(HttpOperation { method, location })
+    } else {
+        None
+    };
+
+    let mut depth = 0;
+    let mut documentation = None;
+    let mut input = None;
+    let mut output = None;
+    let mut faults = Vec::new();
+    let mut extensions = Vec::new();
+
+    while let Some(v) = iter.next() {
+        match (v?, depth) {
+            (XmlEvent::StartElement { ref name, .. }, 0) if is_wsdl2_element(name,
+                                                                             "documentation") => {
+                documentation = Some(WsdlDocumentation::read(iter)?)
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ..
+             },
+             0) if is_wsdl2_element(name, "input") => {
+                let (documentation, extensions) = read_documented(iter)?;
+                input = Some(WsdlInputBinding {
+                                 documentation,
+                                 name: find_attribute("messageLabel", attributes),
+                                 soap_body: None,
+                                 soap_headers: Vec::new(),
+                                 extensions,
+                                 extension_attributes: extension_attributes(attributes),
+                             });
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ..
+             },
+             0) if is_wsdl2_element(name, "output") => {
+                let (documentation, extensions) = read_documented(iter)?;
+                output = Some(WsdlOutputBinding {
+                                  documentation,
+                                  name: find_attribute("messageLabel", attributes),
+                                  soap_body: None,
+                                  soap_headers: Vec::new(),
+                                  extensions,
+                                  extension_attributes: extension_attributes(attributes),
+                              });
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if is_wsdl2_element(name, "infault") || is_wsdl2_element(name, "outfault") => {
+                let fault = find_attribute("ref", attributes)
+                    .ok_or_else(|| {
+                                    ErrorKind::MandatoryAttribute("ref".to_string(),
+                                                                  format!("wsdl:{}",
+                                                                          name.local_name))
+                                })?;
+                let fault = read_qname(&fault, namespace)?;
+                let (documentation, extensions) = read_documented(iter)?;
+
+                faults.push(WsdlFaultBinding {
+                                documentation,
+                                name: fault.local_name,
+                                soap_fault: None,
+                                extensions,
+                                extension_attributes: extension_attributes(attributes),
+                            });
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if !is_wsdl2(name) => {
+                extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
+            }
+            (XmlEvent::StartElement { .. }, _) => depth += 1,
+            (XmlEvent::EndElement { .. }, 0) => break,
+            (XmlEvent::EndElement { .. }, _) => depth -= 1,
+            _ => continue,
+        }
+    }
+
+    Ok(WsdlOperationBinding {
+           documentation,
+           name: reference.local_name,
+           soap_operation,
+           http_operation,
+           extensions,
+           extension_attributes: extension_attributes(attributes),
+           input,
+           output,
+           faults,
+       })
+}
+
+fn read_service(attributes: &[OwnedAttribute], iter: &mut Reader) -> Result<WsdlService> {
+    let name = find_attribute("name", attributes)
+        .ok_or_else(|| {
+                        ErrorKind::MandatoryAttribute("name".to_string(),
+                                                      "wsdl:service".to_string())
+                    })?;
+
+    let mut depth = 0;
+    let mut documentation = None;
+    let mut ports = Vec::new();
+    let mut extensions = Vec::new();
+
+    while let Some(v) = iter.next() {
+        match (v?, depth) {
+            (XmlEvent::StartElement { ref name, .. }, 0) if is_wsdl2_element(name,
+                                                                             "documentation") => {
+                documentation = Some(WsdlDocumentation::read(iter)?)
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if is_wsdl2_element(name, "endpoint") => {
+                let endpoint_name = find_attribute("name", attributes)
+                    .ok_or_else(|| {
+                                    ErrorKind::MandatoryAttribute("name".to_string(),
+                                                                  "wsdl:endpoint".to_string())
+                                })?;
+                let binding = find_attribute("binding", attributes)
+                    .ok_or_else(|| {
+                                    ErrorKind::MandatoryAttribute("binding".to_string(),
+                                                                  "wsdl:endpoint".to_string())
+                                })?;
+                let (documentation, extensions) = read_documented(iter)?;
+
+                ports.push(WsdlPort {
+                               name: endpoint_name,
+                               binding: read_qname(&binding, namespace)?,
+                               // Protocol is taken from the binding once all of them are read.
+                               address: find_attribute("address", attributes).map(|location| {
+                                   WsdlAddress {
+                                       protocol: AddressProtocol::Soap12,
+                                       location,
+                                   }
+                               }),
+                               documentation,
+                               extensions,
+                               extension_attributes: extension_attributes(attributes),
+                           });
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if !is_wsdl2(name) => {
+                extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
+            }
+            (XmlEvent::StartElement { .. }, _) => depth += 1,
+            (XmlEvent::EndElement { .. }, 0) => break,
+            (XmlEvent::EndElement { .. }, _) => depth -= 1,
+            _ => continue,
+        }
+    }
+
+    Ok(WsdlService {
+           documentation,
+           target_namespace: None,
+           imported_from: None,
+           name,
+           ports,
+           extensions,
+           extension_attributes: extension_attributes(attributes),
+       })
+}
+
+/// Creates a message for each input, output and fault of the operations and
+/// makes them refer to it. Messages are named after the interface, the
+/// operation and the message label or fault.
+fn synthesize_messages(port_types: &mut [WsdlPortType],
+                       target_namespace: &Option<String>)
+                       -> Vec<WsdlMessage> {
+    let mut messages = Vec::new();
+    let mut synthesize = |reference: &mut WsdlOperationMessage, name: String| {
+        reference.message = Some(OwnedName {
+                                     local_name: name.clone(),
+                                     namespace: target_namespace.clone(),
+                                     prefix: None,
+                                 });
+        messages.push(WsdlMessage {
+                          documentation: None,
+                          target_namespace: None,
+                          imported_from: None,
+                          name,
+                          parts: reference
+                              .element
+                              .iter()
+                              .map(|element| {
+                                       WsdlMessagePart {
+                                           documentation: None,
+                                           name: "parameters".to_string(),
+                                           element: Some(element.clone()),
+                                           part_type: None,
+                                           extensions: Vec::new(),
+                                           extension_attributes: Vec::new(),
+                                       }
+                                   })
+                              .collect(),
+                          extensions: Vec::new(),
+                          extension_attributes: Vec::new(),
+                      });
+    };
+
+    for port_type in port_types {
+        for operation in &mut port_type.operations {
+            let prefix = format!("{}_{}", port_type.name, operation.name);
+            if let Some(ref mut input) = operation.input {
+                synthesize(input, format!("{}_input", prefix));
+            }
+            if let Some(ref mut output) = operation.output {
+                synthesize(output, format!("{}_output", prefix));
+            }
+            for fault in &mut operation.faults {
+                let name = format!("{}_{}", prefix, fault.name.as_deref().unwrap_or_default());
+                synthesize(fault, name);
+            }
+        }
+    }
+
+    messages
+}
+
+fn assign_address_protocols(wsdl: &mut Wsdl) {
+    let mut protocols = Vec::new();
+
+    for service in &wsdl.services {
+        for port in &service.ports {
+            let protocol = match wsdl.find_binding(&port.binding) {
+                Some(binding) if binding.http_binding.is_some() => AddressProtocol::Http,
+                Some(binding) if binding.soap_version() == Some(SoapVersion::Soap11) => {
+                    AddressProtocol::Soap11
+                }
+                _ => AddressProtocol::Soap12,
+            };
+            protocols.push(protocol);
+        }
+    }
+
+    let addresses = wsdl.services
+        .iter_mut()
+        .flat_map(|s| s.ports.iter_mut())
+        .map(|p| p.address.as_mut());
+
+    for (address, protocol) in addresses.zip(protocols) {
+        if let Some(address) = address {
+            address.protocol = protocol;
+        }
+    }
+}
+
+/// Reads the content of an element that may only hold `wsdl:documentation`
+/// and extension elements.
+fn read_documented(iter: &mut Reader) -> Result<(Option<WsdlDocumentation>, Vec<WsdlExtension>)> {
+    let mut depth = 0;
+    let mut documentation = None;
+    let mut extensions = Vec::new();
+
+    while let Some(v) = iter.next() {
+        match (v?, depth) {
+            (XmlEvent::StartElement { ref name, .. }, 0) if is_wsdl2_element(name,
+                                                                             "documentation") => {
+                documentation = Some(WsdlDocumentation::read(iter)?)
+            }
+            (XmlEvent::StartElement {
+                 ref name,
+                 ref attributes,
+                 ref namespace,
+             },
+             0) if !is_wsdl2(name) => {
+                extensions.push(XmlElement::read(name, attributes, namespace, iter)?)
+            }
+            (XmlEvent::StartElement { .. }, _) => depth += 1,
+            (XmlEvent::EndElement { .. }, 0) => break,
+            (XmlEvent::EndElement { .. }, _) => depth -= 1,
+            _ => continue,
+        }
+    }
+
+    Ok((documentation, extensions))
+}
+
+/// Attributes from foreign namespaces, except the SOAP and HTTP binding
+/// attributes that are read into the model.
+fn extension_attributes(attributes: &[OwnedAttribute]) -> Vec<OwnedAttribute> {
+    schema::extension_attributes(attributes)
+        .into_iter()
+        .filter(|a| {
+                    let namespace = a.name.namespace.as_deref();
+                    namespace != Some(NS_WSDL2_SOAP) && namespace != Some(NS_WSDL2_HTTP)
+                })
+        .collect()
+}
+
+/// Reads an `element` attribute, the `#any`, `#none` and `#other` tokens
+/// do not name an element.
+fn read_element_reference(attributes: &[OwnedAttribute],
+                          namespace: &Namespace)
+                          -> Result<Option<OwnedName>> {
+    match find_attribute("element", attributes) {
+        Some(ref element) if element.starts_with('#') => Ok(None),
+        Some(ref element) => Ok(Some(read_qname(element, namespace)?)),
+        None => Ok(None),
+    }
+}
+
+fn read_qname_list(value: Option<String>, namespace: &Namespace) -> Result<Vec<OwnedName>> {
+    match value {
+        Some(value) => value.split_whitespace().map(|v| read_qname(v, namespace)).collect(),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn find_ns_attribute(namespace: &str,
+                     name: &str,
+                     attributes: &[OwnedAttribute])
+                     -> Option<String> {
+    attributes
+        .iter()
+        .find(|a| {
+                  a.name.namespace.as_deref() == Some(namespace) &&
+                  a.name.local_name == name
+              })
+        .map(|a| a.value.clone())
+}
+
+fn is_wsdl2_element(name: &OwnedName, local_name: &str) -> bool {
+    is_wsdl2(name) && name.local_name == local_name
+}
+
+fn is_wsdl2(name: &OwnedName) -> bool {
+    name.namespace.as_deref() == Some(NS_WSDL2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIPTION: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<description xmlns="http://www.w3.org/ns/wsdl"
+             xmlns:tns="urn:hello"
+             xmlns:msg="urn:hello:messages"
+             xmlns:wsoap="http://www.w3.org/ns/wsdl/soap"
+             xmlns:whttp="http://www.w3.org/ns/wsdl/http"
+             targetNamespace="urn:hello">
+    <documentation>Hello service</documentation>
+    <types/>
+    <interface name="HelloInterface">
+        <fault name="HelloFault" element="msg:HelloFault"/>
+        <operation name="sayHello" pattern="http://www.w3.org/ns/wsdl/in-out">
+            <input messageLabel="In" element="msg:SayHello"/>
+            <output messageLabel="Out" element="msg:SayHelloResponse"/>
+            <outfault ref="tns:HelloFault" messageLabel="Out"/>
+        </operation>
+    </interface>
+    <binding name="HelloSoap12Binding" interface="tns:HelloInterface"
+             type="http://www.w3.org/ns/wsdl/soap"
+             wsoap:version="1.2"
+             wsoap:protocol="http://www.w3.org/2003/05/soap/bindings/HTTP/">
+        <operation ref="tns:sayHello" wsoap:action="urn:sayHello"/>
+    </binding>
+    <binding name="HelloHttpBinding" interface="tns:HelloInterface"
+             type="http://www.w3.org/ns/wsdl/http">
+        <operation ref="tns:sayHello" whttp:method="GET" whttp:location="sayHello"/>
+    </binding>
+    <service name="HelloService" interface="tns:HelloInterface">
+        <endpoint name="Soap12Endpoint" binding="tns:HelloSoap12Binding"
+                  address="http://example.com/hello/soap12"/>
+        <endpoint name="HttpEndpoint" binding="tns:HelloHttpBinding"
+                  address="http://example.com/hello/http"/>
+    </service>
+</description>
+"#;
+
+    #[test]
+    fn reads_interface_test() {
+        let wsdl = Wsdl::parse(DESCRIPTION.as_bytes()).unwrap();
+
+        assert_eq!(WsdlVersion::Wsdl20, wsdl.version);
+        assert_eq!("Hello service", wsdl.documentation.as_ref().unwrap().text);
+
+        let operation = &wsdl.port_types[0].operations[0];
+        let input = operation.input.as_ref().unwrap();
+        assert_eq!(Some("http://www.w3.org/ns/wsdl/in-out".to_string()), operation.pattern);
+        assert_eq!(Some("In".to_string()), input.name);
+        assert_eq!(Some("urn:hello:messages".to_string()),
+                   input.element.as_ref().unwrap().namespace);
+        assert_eq!("HelloFault",
+                   operation.faults[0].element.as_ref().unwrap().local_name);
+    }
+
+    #[test]
+    fn matches_faults_by_qname_test() {
+        let description = DESCRIPTION.replace(r#"ref="tns:HelloFault""#, r#"ref="msg:HelloFault""#);
+        let wsdl = Wsdl::parse(description.as_bytes()).unwrap();
+
+        let fault = &wsdl.port_types[0].operations[0].faults[0];
+        assert_eq!(Some("HelloFault".to_string()), fault.name);
+        assert!(fault.element.is_none());
+    }
+
+    #[test]
+    fn synthesizes_messages_test() {
+        let wsdl = Wsdl::parse(DESCRIPTION.as_bytes()).unwrap();
+
+        let operation = &wsdl.port_types[0].operations[0];
+        let input = operation.input.as_ref().unwrap().message.as_ref().unwrap();
+        assert_eq!("HelloInterface_sayHello_input", input.local_name);
+        assert_eq!(Some("urn:hello".to_string()), input.namespace);
+        assert_eq!(3, wsdl.messages.len());
+
+        let message = &wsdl.messages[0];
+        assert_eq!(Some("urn:hello".to_string()), message.target_namespace);
+        assert_eq!("parameters", message.parts[0].name);
+        assert_eq!("SayHello", message.parts[0].element.as_ref().unwrap().local_name);
+
+        // Written as WSDL 1.1, the operations refer to the messages.
+        let written = Wsdl::parse(wsdl.to_xml().as_bytes()).unwrap();
+        let operation = &written.port_types[0].operations[0];
+        assert_eq!(WsdlVersion::Wsdl11, written.version);
+        assert_eq!(3, written.messages.len());
+        assert_eq!("HelloInterface_sayHello_output",
+                   operation.output.as_ref().unwrap().message.as_ref().unwrap().local_name);
+        assert_eq!("HelloInterface_sayHello_HelloFault",
+                   operation.faults[0].message.as_ref().unwrap().local_name);
+        assert_eq!("HelloFault",
+                   written.messages[2].parts[0].element.as_ref().unwrap().local_name);
+    }
+
+    #[test]
+    fn reads_bindings_and_endpoints_test() {
+        let wsdl = Wsdl::parse(DESCRIPTION.as_bytes()).unwrap();
+
+        let soap = &wsdl.bindings[0];
+        assert_eq!("HelloInterface", soap.port_type.local_name);
+        assert_eq!(Some(SoapVersion::Soap12), soap.soap_version());
+        assert_eq!(Some("urn:sayHello"), soap.operations[0].soap_action());
+
+        let http = &wsdl.bindings[1];
+        let operation = http.operations[0].http_operation.as_ref().unwrap();
+        assert!(http.http_binding.is_some());
+        assert_eq!(Some("GET".to_string()), operation.method);
+        assert_eq!(Some("sayHello".to_string()), operation.location);
+
+        let service = &wsdl.services[0];
+        assert_eq!(Some("http://example.com/hello/soap12"),
+                   service.endpoint("Soap12Endpoint"));
+        assert_eq!(AddressProtocol::Soap12,
+                   service.ports[0].address.as_ref().unwrap().protocol);
+        assert_eq!(AddressProtocol::Http,
+                   service.ports[1].address.as_ref().unwrap().protocol);
+    }
+}
@@ -0,0 +1,1015 @@
+use super::element::XmlElement;
+use super::errors::*;
+use super::import::is_xsd;
+use super::schema::read_qname;
+
+use std::borrow::Cow;
+
+use xml::name::OwnedName;
+use xml::namespace::Namespace;
+
+/// Components of an XML Schema document. Names of global components are in
+/// the target namespace, references to other components are resolved QNames.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XsdSchema {
+    pub target_namespace: Option<String>,
+    pub element_form_default: XsdForm,
+    pub attribute_form_default: XsdForm,
+    pub imports: Vec<XsdImport>,
+    /// Locations of the included schema documents.
+    pub includes: Vec<String>,
+    pub elements: Vec<XsdElement>,
+    pub attributes: Vec<XsdAttribute>,
+    pub complex_types: Vec<XsdComplexType>,
+    pub simple_types: Vec<XsdSimpleType>,
+    pub groups: Vec<XsdGroup>,
+    pub attribute_groups: Vec<XsdAttributeGroup>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XsdForm {
+    Qualified,
+    Unqualified,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct XsdImport {
+    pub namespace: Option<String>,
+    pub location: Option<String>,
+}
+
+/// Element declaration, global or local to a model group. Local elements are
+/// in the target namespace only when their form is qualified.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XsdElement {
+    pub name: String,
+    pub namespace: Option<String>,
+    /// Global element declared with `ref`, its declaration holds the type.
+    pub reference: Option<OwnedName>,
+    /// `None` stands for `xs:anyType`.
+    pub element_type: Option<XsdTypeRef>,
+    pub min_occurs: u32,
+    /// `None` when unbounded.
+    pub max_occurs: Option<u32>,
+    pub nillable: bool,
+    pub is_abstract: bool,
+    pub default: Option<String>,
+    pub fixed: Option<String>,
+    pub substitution_group: Option<OwnedName>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct XsdAttribute {
+    pub name: String,
+    pub namespace: Option<String>,
+    /// Global attribute declared with `ref`, its declaration holds the type.
+    pub reference: Option<OwnedName>,
+    /// `None` stands for `xs:anySimpleType`.
+    pub attribute_type: Option<XsdTypeRef>,
+    pub attribute_use: XsdAttributeUse,
+    pub default: Option<String>,
+    pub fixed: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XsdAttributeUse {
+    Optional,
+    Required,
+    Prohibited,
+}
+
+/// Type of a declaration, either named or defined in place.
+#[derive(Debug, Clone, PartialEq)]
+pub enum XsdTypeRef {
+    Named(OwnedName),
+    Complex(Box<XsdComplexType>),
+    Simple(Box<XsdSimpleType>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct XsdComplexType {
+    /// `None` for anonymous types.
+    pub name: Option<String>,
+    pub is_abstract: bool,
+    pub mixed: bool,
+    pub content: XsdContent,
+    /// Attributes declared by the type itself, not the ones of its base.
+    pub attributes: Vec<XsdAttribute>,
+    /// Referenced attribute groups, their attributes are the type's own too.
+    pub attribute_groups: Vec<OwnedName>,
+    pub any_attribute: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum XsdContent {
+    Empty,
+    Group(XsdModelGroup),
+    /// `simpleContent`, character data with attributes.
+    Simple(XsdDerivation),
+    /// `complexContent`, derived from another complex type.
+    Complex(XsdDerivation),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct XsdDerivation {
+    pub method: XsdDerivationMethod,
+    pub base: OwnedName,
+    /// Model group appended to the one of the base by an extension or
+    /// replacing it by a restriction.
+    pub group: Option<XsdModelGroup>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XsdDerivationMethod {
+    Extension,
+    Restriction,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct XsdModelGroup {
+    pub compositor: XsdCompositor,
+    pub min_occurs: u32,
+    pub max_occurs: Option<u32>,
+    pub particles: Vec<XsdParticle>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XsdCompositor {
+    Sequence,
+    Choice,
+    All,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum XsdParticle {
+    Element(Box<XsdElement>),
+    Group(XsdModelGroup),
+    /// Reference to a named model group, see `SchemaSet::effective_type`.
+    GroupRef(XsdGroupRef),
+    Any(XsdAny),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct XsdGroupRef {
+    pub reference: OwnedName,
+    pub min_occurs: u32,
+    pub max_occurs: Option<u32>,
+}
+
+/// Named model group defined by `xs:group`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XsdGroup {
+    pub name: String,
+    pub group: XsdModelGroup,
+}
+
+/// Named set of attribute uses defined by `xs:attributeGroup`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XsdAttributeGroup {
+    pub name: String,
+    pub attributes: Vec<XsdAttribute>,
+    pub attribute_groups: Vec<OwnedName>,
+    pub any_attribute: bool,
+}
+
+/// Wildcard for elements of other schemas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XsdAny {
+    /// Namespace constraint as written, `##any` by default.
+    pub namespace: String,
+    pub min_occurs: u32,
+    pub max_occurs: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct XsdSimpleType {
+    /// `None` for anonymous types.
+    pub name: Option<String>,
+    pub variety: XsdSimpleVariety,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum XsdSimpleVariety {
+    Restriction {
+        base: XsdTypeRef,
+        facets: Vec<XsdFacet>,
+    },
+    List { item_type: XsdTypeRef },
+    Union { member_types: Vec<XsdTypeRef> },
+}
+
+/// Constraining facet of a simple type restriction. Bounds are kept in their
+/// lexical form, their meaning depends on the base type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum XsdFacet {
+    Enumeration(String),
+    Pattern(String),
+    Length(usize),
+    MinLength(usize),
+    MaxLength(usize),
+    MinInclusive(String),
+    MaxInclusive(String),
+    MinExclusive(String),
+    MaxExclusive(String),
+    TotalDigits(usize),
+    FractionDigits(usize),
+    WhiteSpace(XsdWhiteSpace),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XsdWhiteSpace {
+    Preserve,
+    /// Tabs and line breaks become spaces.
+    Replace,
+    /// Replaced, then runs of spaces become one and leading and trailing
+    /// spaces are removed.
+    Collapse,
+}
+
+/// Defaults of the schema document a component is read from.
+struct Context<'a> {
+    target_namespace: &'a Option<String>,
+    element_form_default: XsdForm,
+    attribute_form_default: XsdForm,
+    /// References without a namespace are in the target namespace.
+    chameleon: bool,
+}
+
+impl XsdSchema {
+    /// Reads the components of an `xs:schema` element. Annotations,
+    /// identity constraints and notations are skipped.
+    pub fn read(schema: &XmlElement) -> Result<XsdSchema> {
+        let mut errors = Vec::new();
+        let model = XsdSchema::read_in(schema, None, &mut errors)?;
+
+        match errors.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(model),
+        }
+    }
+
+    /// Reads the components of an `xs:schema` element like `read` does, but
+    /// skips the top-level components that cannot be read and appends their
+    /// errors to `errors`.
+    pub fn read_lenient(schema: &XmlElement, errors: &mut Vec<Error>) -> Result<XsdSchema> {
+        XsdSchema::read_in(schema, None, errors)
+    }
+
+    /// Reads a schema document included by a schema of `target_namespace`.
+    /// A document without a target namespace of its own takes the one of
+    /// the including schema, so do the references it makes to components
+    /// without a namespace.
+    pub fn read_included(schema: &XmlElement,
+                         target_namespace: &Option<String>)
+                         -> Result<XsdSchema> {
+        let mut errors = Vec::new();
+        let model = XsdSchema::read_in(schema, Some(target_namespace), &mut errors)?;
+
+        match errors.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(model),
+        }
+    }
+
+    fn read_in(schema: &XmlElement,
+               including: Option<&Option<String>>,
+               errors: &mut Vec<Error>)
+               -> Result<XsdSchema> {
+        let scope = in_scope(&Namespace::empty(), schema).into_owned();
+
+        let declared = schema.attribute("targetNamespace").map(String::from);
+        let chameleon = declared.is_none() && including.and_then(|ns| ns.as_ref()).is_some();
+        let target_namespace = if chameleon {
+            including.cloned().unwrap_or_default()
+        } else {
+            declared
+        };
+        let element_form_default = read_form(schema, "elementFormDefault")?
+            .unwrap_or(XsdForm::Unqualified);
+        let attribute_form_default = read_form(schema, "attributeFormDefault")?
+            .unwrap_or(XsdForm::Unqualified);
+
+        let mut imports = Vec::new();
+        let mut includes = Vec::new();
+        let mut elements = Vec::new();
+        let mut attributes = Vec::new();
+        let mut complex_types = Vec::new();
+        let mut simple_types = Vec::new();
+        let mut groups = Vec::new();
+        let mut attribute_groups = Vec::new();
+
+        {
+            let context = Context {
+                target_namespace: &target_namespace,
+                element_form_default,
+                attribute_form_default,
+                chameleon,
+            };
+
+            for child in xsd_elements(schema) {
+                let scope = in_scope(&scope, child);
+                let read = match child.name.local_name.as_str() {
+                    "import" => {
+                        imports.push(XsdImport {
+                                         namespace: child.attribute("namespace").map(String::from),
+                                         location: child
+                                             .attribute("schemaLocation")
+                                             .map(String::from),
+                                     });
+                        Ok(())
+                    }
+                    "include" => mandatory(child, "schemaLocation").map(|l| includes.push(l)),
+                    "element" => context.element(child, &scope, true).map(|e| elements.push(e)),
+                    "attribute" => {
+                        context.attribute(child, &scope, true).map(|a| attributes.push(a))
+                    }
+                    "complexType" => {
+                        context.complex_type(child, &scope).map(|t| complex_types.push(t))
+                    }
+                    "simpleType" => {
+                        context.simple_type(child, &scope).map(|t| simple_types.push(t))
+                    }
+                    "group" => context.group(child, &scope).map(|g| groups.push(g)),
+                    "attributeGroup" => {
+                        context.attribute_group(child, &scope).map(|g| attribute_groups.push(g))
+                    }
+                    _ => continue,
+                };
+
+                if let Err(e) = read {
+                    errors.push(e);
+                }
+            }
+        }
+
+        Ok(XsdSchema {
+               target_namespace,
+               element_form_default,
+               attribute_form_default,
+               imports,
+               includes,
+               elements,
+               attributes,
+               complex_types,
+               simple_types,
+               groups,
+               attribute_groups,
+           })
+    }
+
+    pub fn find_element(&self, name: &str) -> Option<&XsdElement> {
+        self.elements.iter().find(|e| e.name == name)
+    }
+
+    pub fn find_complex_type(&self, name: &str) -> Option<&XsdComplexType> {
+        self.complex_types
+            .iter()
+            .find(|t| t.name.as_deref() == Some(name))
+    }
+
+    pub fn find_simple_type(&self, name: &str) -> Option<&XsdSimpleType> {
+        self.simple_types
+            .iter()
+            .find(|t| t.name.as_deref() == Some(name))
+    }
+
+    pub fn find_group(&self, name: &str) -> Option<&XsdGroup> {
+        self.groups.iter().find(|g| g.name == name)
+    }
+
+    pub fn find_attribute_group(&self, name: &str) -> Option<&XsdAttributeGroup> {
+        self.attribute_groups.iter().find(|g| g.name == name)
+    }
+}
+
+impl<'a> Context<'a> {
+    fn element(&self,
+               element: &XmlElement,
+               scope: &Namespace,
+               global: bool)
+               -> Result<XsdElement> {
+        let reference = match element.attribute("ref") {
+            Some(value) if !global => Some(self.qname(value, scope)?),
+            _ => None,
+        };
+
+        let (name, namespace) = match reference {
+            Some(ref reference) => (reference.local_name.clone(), reference.namespace.clone()),
+            None => {
+                let form = match read_form(element, "form")? {
+                    _ if global => XsdForm::Qualified,
+                    Some(form) => form,
+                    None => self.element_form_default,
+                };
+                (mandatory(element, "name")?, self.namespace(form))
+            }
+        };
+
+        let (min_occurs, max_occurs) = read_occurs(element)?;
+
+        Ok(XsdElement {
+               name,
+               namespace,
+               reference,
+               element_type: self.declared_type(element, scope)?,
+               min_occurs,
+               max_occurs,
+               nillable: read_bool(element, "nillable")?,
+               is_abstract: read_bool(element, "abstract")?,
+               default: element.attribute("default").map(String::from),
+               fixed: element.attribute("fixed").map(String::from),
+               substitution_group: match element.attribute("substitutionGroup") {
+                   Some(value) => Some(self.qname(value, scope)?),
+                   None => None,
+               },
+           })
+    }
+
+    fn attribute(&self,
+                 attribute: &XmlElement,
+                 scope: &Namespace,
+                 global: bool)
+                 -> Result<XsdAttribute> {
+        let reference = match attribute.attribute("ref") {
+            Some(value) if !global => Some(self.qname(value, scope)?),
+            _ => None,
+        };
+
+        let (name, namespace) = match reference {
+            Some(ref reference) => (reference.local_name.clone(), reference.namespace.clone()),
+            None => {
+                let form = match read_form(attribute, "form")? {
+                    _ if global => XsdForm::Qualified,
+                    Some(form) => form,
+                    None => self.attribute_form_default,
+                };
+                (mandatory(attribute, "name")?, self.namespace(form))
+            }
+        };
+
+        let attribute_use = match attribute.attribute("use") {
+            None | Some("optional") => XsdAttributeUse::Optional,
+            Some("required") => XsdAttributeUse::Required,
+            Some("prohibited") => XsdAttributeUse::Prohibited,
+            Some(value) => return Err(invalid_value(attribute, "use", value)),
+        };
+
+        Ok(XsdAttribute {
+               name,
+               namespace,
+               reference,
+               attribute_type: self.declared_type(attribute, scope)?,
+               attribute_use,
+               default: attribute.attribute("default").map(String::from),
+               fixed: attribute.attribute("fixed").map(String::from),
+           })
+    }
+
+    /// Type given by the `type` attribute or defined by a child element.
+    fn declared_type(&self, element: &XmlElement, scope: &Namespace) -> Result<Option<XsdTypeRef>> {
+        if let Some(value) = element.attribute("type") {
+            return Ok(Some(XsdTypeRef::Named(self.qname(value, scope)?)));
+        }
+
+        for child in xsd_elements(element) {
+            let scope = in_scope(scope, child);
+            match child.name.local_name.as_str() {
+                "complexType" => {
+                    return Ok(Some(XsdTypeRef::Complex(Box::new(self.complex_type(child,
+                                                                                  &scope)?))))
+                }
+                "simpleType" => {
+                    return Ok(Some(XsdTypeRef::Simple(Box::new(self.simple_type(child,
+                                                                                &scope)?))))
+                }
+                _ => continue,
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn complex_type(&self, complex_type: &XmlElement, scope: &Namespace) -> Result<XsdComplexType> {
+        let mut mixed = read_bool(complex_type, "mixed")?;
+        let mut content = XsdContent::Empty;
+        let (mut attributes, mut attribute_groups, mut any_attribute) =
+            self.attribute_uses(complex_type, scope)?;
+
+        for child in xsd_elements(complex_type) {
+            let scope = in_scope(scope, child);
+            match child.name.local_name.as_str() {
+                "sequence" | "choice" | "all" => {
+                    content = XsdContent::Group(self.model_group(child, &scope)?)
+                }
+                "simpleContent" | "complexContent" => {
+                    if child.attribute("mixed").is_some() {
+                        mixed = read_bool(child, "mixed")?;
+                    }
+
+                    let derivation = xsd_elements(child)
+                        .into_iter()
+                        .find(|d| d.name.local_name == "extension" ||
+                                  d.name.local_name == "restriction")
+                        .ok_or_else(|| {
+                                        ErrorKind::MissingElement(format!("xs:{}", child.name.local_name))
+                                    })?;
+                    let scope = in_scope(&scope, derivation);
+                    let (added, groups, any) = self.attribute_uses(derivation, &scope)?;
+                    attributes.extend(added);
+                    attribute_groups.extend(groups);
+                    any_attribute |= any;
+
+                    let derivation = self.derivation(derivation, &scope)?;
+                    content = if child.name.local_name == "simpleContent" {
+                        XsdContent::Simple(derivation)
+                    } else {
+                        XsdContent::Complex(derivation)
+                    };
+                }
+                "group" => {
+                    content = XsdContent::Group(sequence(self.group_ref(child, &scope)?))
+                }
+                _ => continue,
+            }
+        }
+
+        Ok(XsdComplexType {
+               name: complex_type.attribute("name").map(String::from),
+               is_abstract: read_bool(complex_type, "abstract")?,
+               mixed,
+               content,
+               attributes,
+               attribute_groups,
+               any_attribute,
+           })
+    }
+
+    /// Reads the attribute declarations, attribute group references and
+    /// attribute wildcard among the children of `parent`.
+    fn attribute_uses(&self,
+                      parent: &XmlElement,
+                      scope: &Namespace)
+                      -> Result<(Vec<XsdAttribute>, Vec<OwnedName>, bool)> {
+        let mut attributes = Vec::new();
+        let mut attribute_groups = Vec::new();
+        let mut any_attribute = false;
+
+        for child in xsd_elements(parent) {
+            let scope = in_scope(scope, child);
+            match child.name.local_name.as_str() {
+                "attribute" => attributes.push(self.attribute(child, &scope, false)?),
+                "attributeGroup" => {
+                    attribute_groups.push(self.qname(&mandatory(child, "ref")?, &scope)?)
+                }
+                "anyAttribute" => any_attribute = true,
+                _ => continue,
+            }
+        }
+
+        Ok((attributes, attribute_groups, any_attribute))
+    }
+
+    /// Reads an `extension` or `restriction` of `simpleContent` or
+    /// `complexContent`, its attributes are read by `attribute_uses`.
+    fn derivation(&self, derivation: &XmlElement, scope: &Namespace) -> Result<XsdDerivation> {
+        let method = if derivation.name.local_name == "extension" {
+            XsdDerivationMethod::Extension
+        } else {
+            XsdDerivationMethod::Restriction
+        };
+        let mut group = None;
+
+        for child in xsd_elements(derivation) {
+            let scope = in_scope(scope, child);
+            match child.name.local_name.as_str() {
+                "sequence" | "choice" | "all" => group = Some(self.model_group(child, &scope)?),
+                "group" => group = Some(sequence(self.group_ref(child, &scope)?)),
+                _ => continue,
+            }
+        }
+
+        Ok(XsdDerivation {
+               method,
+               base: self.qname(&mandatory(derivation, "base")?, scope)?,
+               group,
+           })
+    }
+
+    fn model_group(&self, group: &XmlElement, scope: &Namespace) -> Result<XsdModelGroup> {
+        let compositor = match group.name.local_name.as_str() {
+            "sequence" => XsdCompositor::Sequence,
+            "choice" => XsdCompositor::Choice,
+            _ => XsdCompositor::All,
+        };
+        let (min_occurs, max_occurs) = read_occurs(group)?;
+        let mut particles = Vec::new();
+
+        for child in xsd_elements(group) {
+            let scope = in_scope(scope, child);
+            match child.name.local_name.as_str() {
+                "element" => {
+                    let element = self.element(child, &scope, false)?;
+                    particles.push(XsdParticle::Element(Box::new(element)))
+                }
+                "sequence" | "choice" | "all" => {
+                    particles.push(XsdParticle::Group(self.model_group(child, &scope)?))
+                }
+                "group" => particles.push(self.group_ref(child, &scope)?),
+                "any" => {
+                    let (min_occurs, max_occurs) = read_occurs(child)?;
+                    particles.push(XsdParticle::Any(XsdAny {
+                                                        namespace: child
+                                                            .attribute("namespace")
+                                                            .unwrap_or("##any")
+                                                            .to_string(),
+                                                        min_occurs,
+                                                        max_occurs,
+                                                    }))
+                }
+                _ => continue,
+            }
+        }
+
+        Ok(XsdModelGroup {
+               compositor,
+               min_occurs,
+               max_occurs,
+               particles,
+           })
+    }
+
+    fn group_ref(&self, group: &XmlElement, scope: &Namespace) -> Result<XsdParticle> {
+        let (min_occurs, max_occurs) = read_occurs(group)?;
+        Ok(XsdParticle::GroupRef(XsdGroupRef {
+                                     reference: self.qname(&mandatory(group, "ref")?, scope)?,
+                                     min_occurs,
+                                     max_occurs,
+                                 }))
+    }
+
+    /// Reads a global `xs:group`, the definition of a named model group.
+    fn group(&self, group: &XmlElement, scope: &Namespace) -> Result<XsdGroup> {
+        let model_group = xsd_elements(group)
+            .into_iter()
+            .find(|g| {
+                      let name = g.name.local_name.as_str();
+                      name == "sequence" || name == "choice" || name == "all"
+                  })
+            .ok_or_else(|| ErrorKind::MissingElement(xsd_name(group)))?;
+
+        Ok(XsdGroup {
+               name: mandatory(group, "name")?,
+               group: self.model_group(model_group, &in_scope(scope, model_group))?,
+           })
+    }
+
+    /// Reads a global `xs:attributeGroup`, its attributes are local ones.
+    fn attribute_group(&self,
+                       attribute_group: &XmlElement,
+                       scope: &Namespace)
+                       -> Result<XsdAttributeGroup> {
+        let (attributes, attribute_groups, any_attribute) =
+            self.attribute_uses(attribute_group, scope)?;
+
+        Ok(XsdAttributeGroup {
+               name: mandatory(attribute_group, "name")?,
+               attributes,
+               attribute_groups,
+               any_attribute,
+           })
+    }
+
+    fn simple_type(&self, simple_type: &XmlElement, scope: &Namespace) -> Result<XsdSimpleType> {
+        let mut variety = None;
+
+        for child in xsd_elements(simple_type) {
+            let scope = in_scope(scope, child);
+            variety = match child.name.local_name.as_str() {
+                "restriction" => {
+                    Some(XsdSimpleVariety::Restriction {
+                             base: self.simple_type_ref(child, "base", &scope)?,
+                             facets: read_facets(child)?,
+                         })
+                }
+                "list" => {
+                    Some(XsdSimpleVariety::List {
+                             item_type: self.simple_type_ref(child, "itemType", &scope)?,
+                         })
+                }
+                "union" => {
+                    let mut member_types = Vec::new();
+                    if let Some(value) = child.attribute("memberTypes") {
+                        for member in value.split_whitespace() {
+                            member_types.push(XsdTypeRef::Named(self.qname(member, &scope)?));
+                        }
+                    }
+                    for member in xsd_elements(child) {
+                        if member.name.local_name == "simpleType" {
+                            let scope = in_scope(&scope, member);
+                            member_types.push(XsdTypeRef::Simple(Box::new(self.simple_type(member,
+                                                                                           &scope)?)));
+                        }
+                    }
+                    Some(XsdSimpleVariety::Union { member_types })
+                }
+                _ => continue,
+            };
+        }
+
+        Ok(XsdSimpleType {
+               name: simple_type.attribute("name").map(String::from),
+               variety: variety
+                   .ok_or_else(|| ErrorKind::MissingElement("xs:restriction".to_string()))?,
+           })
+    }
+
+    /// Simple type named by `attribute` or defined by a `simpleType` child.
+    fn simple_type_ref(&self,
+                       element: &XmlElement,
+                       attribute: &str,
+                       scope: &Namespace)
+                       -> Result<XsdTypeRef> {
+        if let Some(value) = element.attribute(attribute) {
+            return Ok(XsdTypeRef::Named(self.qname(value, scope)?));
+        }
+
+        match xsd_elements(element)
+                  .into_iter()
+                  .find(|c| c.name.local_name == "simpleType") {
+            Some(child) => {
+                let scope = in_scope(scope, child);
+                Ok(XsdTypeRef::Simple(Box::new(self.simple_type(child, &scope)?)))
+            }
+            None => Err(ErrorKind::MandatoryAttribute(attribute.to_string(), xsd_name(element)).into()),
+        }
+    }
+
+    fn qname(&self, value: &str, scope: &Namespace) -> Result<OwnedName> {
+        let mut name = read_qname(value, scope)?;
+        if self.chameleon && name.namespace.is_none() {
+            name.namespace = self.target_namespace.clone();
+        }
+
+        Ok(name)
+    }
+
+    fn namespace(&self, form: XsdForm) -> Option<String> {
+        match form {
+            XsdForm::Qualified => self.target_namespace.clone(),
+            XsdForm::Unqualified => None,
+        }
+    }
+}
+
+/// Reads the models of the `xs:schema` elements among `elements`.
+pub fn read_schemas(elements: &[XmlElement]) -> Result<Vec<XsdSchema>> {
+    elements
+        .iter()
+        .filter(|e| is_xsd(&e.name) && e.name.local_name == "schema")
+        .map(XsdSchema::read)
+        .collect()
+}
+
+/// Sequence of a single particle, the content of a group reference in place
+/// of a compositor.
+fn sequence(particle: XsdParticle) -> XsdModelGroup {
+    XsdModelGroup {
+        compositor: XsdCompositor::Sequence,
+        min_occurs: 1,
+        max_occurs: Some(1),
+        particles: vec![particle],
+    }
+}
+
+/// Namespaces in scope of `element`, given the ones of its parent.
+fn in_scope<'a>(parent: &'a Namespace, element: &XmlElement) -> Cow<'a, Namespace> {
+    if element.namespaces.is_empty() {
+        return Cow::Borrowed(parent);
+    }
+
+    let mut scope = parent.clone();
+    for (prefix, uri) in &element.namespaces {
+        scope.0.insert(prefix.clone(), uri.clone());
+    }
+    Cow::Owned(scope)
+}
+
+/// Child elements from the XML Schema namespace, annotations included.
+fn xsd_elements(element: &XmlElement) -> Vec<&XmlElement> {
+    element
+        .elements()
+        .into_iter()
+        .filter(|e| is_xsd(&e.name))
+        .collect()
+}
+
+fn xsd_name(element: &XmlElement) -> String {
+    format!("xs:{}", element.name.local_name)
+}
+
+fn mandatory(element: &XmlElement, attribute: &str) -> Result<String> {
+    element
+        .attribute(attribute)
+        .map(String::from)
+        .ok_or_else(|| ErrorKind::MandatoryAttribute(attribute.to_string(), xsd_name(element)).into())
+}
+
+fn invalid_value(element: &XmlElement, attribute: &str, value: &str) -> Error {
+    ErrorKind::InvalidAttributeValue(attribute.to_string(), xsd_name(element), value.to_string())
+        .into()
+}
+
+fn read_bool(element: &XmlElement, attribute: &str) -> Result<bool> {
+    match element.attribute(attribute) {
+        None | Some("false") | Some("0") => Ok(false),
+        Some("true") | Some("1") => Ok(true),
+        Some(value) => Err(invalid_value(element, attribute, value)),
+    }
+}
+
+fn read_form(element: &XmlElement, attribute: &str) -> Result<Option<XsdForm>> {
+    match element.attribute(attribute) {
+        None => Ok(None),
+        Some("qualified") => Ok(Some(XsdForm::Qualified)),
+        Some("unqualified") => Ok(Some(XsdForm::Unqualified)),
+        Some(value) => Err(invalid_value(element, attribute, value)),
+    }
+}
+
+fn read_facets(restriction: &XmlElement) -> Result<Vec<XsdFacet>> {
+    let mut facets = Vec::new();
+
+    for facet in xsd_elements(restriction) {
+        let value = match facet.name.local_name.as_str() {
+            "simpleType" | "annotation" => continue,
+            _ => mandatory(facet, "value")?,
+        };
+        let length = || -> Result<usize> {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| invalid_value(facet, "value", &value))
+        };
+
+        facets.push(match facet.name.local_name.as_str() {
+                        "enumeration" => XsdFacet::Enumeration(value.clone()),
+                        "pattern" => XsdFacet::Pattern(value.clone()),
+                        "length" => XsdFacet::Length(length()?),
+                        "minLength" => XsdFacet::MinLength(length()?),
+                        "maxLength" => XsdFacet::MaxLength(length()?),
+                        "minInclusive" => XsdFacet::MinInclusive(value.clone()),
+                        "maxInclusive" => XsdFacet::MaxInclusive(value.clone()),
+                        "minExclusive" => XsdFacet::MinExclusive(value.clone()),
+                        "maxExclusive" => XsdFacet::MaxExclusive(value.clone()),
+                        "totalDigits" => XsdFacet::TotalDigits(length()?),
+                        "fractionDigits" => XsdFacet::FractionDigits(length()?),
+                        "whiteSpace" => {
+                            XsdFacet::WhiteSpace(match value.as_str() {
+                                                     "preserve" => XsdWhiteSpace::Preserve,
+                                                     "replace" => XsdWhiteSpace::Replace,
+                                                     "collapse" => XsdWhiteSpace::Collapse,
+                                                     _ => return Err(invalid_value(facet, "value", &value)),
+                                                 })
+                        }
+                        _ => continue,
+                    });
+    }
+
+    Ok(facets)
+}
+
+/// `minOccurs` and `maxOccurs` of a particle, both default to one.
+fn read_occurs(element: &XmlElement) -> Result<(u32, Option<u32>)> {
+    let min_occurs = match element.attribute("minOccurs") {
+        Some(value) => {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| invalid_value(element, "minOccurs", value))?
+        }
+        None => 1,
+    };
+    let max_occurs = match element.attribute("maxOccurs") {
+        Some("unbounded") => None,
+        Some(value) => {
+            Some(value
+                     .trim()
+                     .parse()
+                     .map_err(|_| invalid_value(element, "maxOccurs", value))?)
+        }
+        None => Some(1),
+    };
+
+    Ok((min_occurs, max_occurs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::file::example;
+    use super::super::import::NS_XSD;
+    use super::super::schema::Wsdl;
+
+    #[test]
+    fn reads_schema_components_test() {
+        let schema = XmlElement::parse(br#"<?xml version="1.0" encoding="utf-8"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:test"
+           targetNamespace="urn:test" elementFormDefault="qualified">
+    <xs:element name="Order" type="tns:Order" nillable="true"/>
+    <xs:complexType name="Order">
+        <xs:sequence>
+            <xs:element name="Id" type="xs:int"/>
+            <xs:element name="Line" minOccurs="0" maxOccurs="unbounded">
+                <xs:complexType>
+                    <xs:choice>
+                        <xs:element ref="tns:Note"/>
+                        <xs:element name="Sku" type="xs:string" form="unqualified"/>
+                    </xs:choice>
+                </xs:complexType>
+            </xs:element>
+        </xs:sequence>
+        <xs:attribute name="status" type="tns:Status" use="required"/>
+    </xs:complexType>
+    <xs:simpleType name="Status">
+        <xs:list itemType="xs:token"/>
+    </xs:simpleType>
+</xs:schema>
+"#)
+                .unwrap();
+        let schema = XsdSchema::read(&schema).unwrap();
+
+        assert_eq!(Some("urn:test".to_string()), schema.target_namespace);
+        assert_eq!(XsdForm::Qualified, schema.element_form_default);
+        assert_eq!(XsdForm::Unqualified, schema.attribute_form_default);
+
+        let order = schema.find_element("Order").unwrap();
+        assert!(order.nillable);
+        assert_eq!(Some(XsdTypeRef::Named(OwnedName::qualified("Order", "urn:test", Some("tns")))),
+                   order.element_type);
+
+        let order = schema.find_complex_type("Order").unwrap();
+        let sequence = match order.content {
+            XsdContent::Group(ref group) => group,
+            ref other => panic!("Unexpected content: {:?}", other),
+        };
+        assert_eq!(XsdCompositor::Sequence, sequence.compositor);
+        let line = match sequence.particles[1] {
+            XsdParticle::Element(ref element) => element,
+            ref other => panic!("Unexpected particle: {:?}", other),
+        };
+        assert_eq!((0, None), (line.min_occurs, line.max_occurs));
+        assert_eq!(Some("urn:test".to_string()), line.namespace);
+
+        let choice = match line.element_type {
+            Some(XsdTypeRef::Complex(ref line_type)) => {
+                match line_type.content {
+                    XsdContent::Group(ref group) => group.particles.clone(),
+                    ref other => panic!("Unexpected content: {:?}", other),
+                }
+            }
+            ref other => panic!("Unexpected type: {:?}", other),
+        };
+        let choice = choice
+            .iter()
+            .filter_map(|p| match *p {
+                            XsdParticle::Element(ref element) => Some(element),
+                            _ => None,
+                        })
+            .collect::<Vec<_>>();
+        assert_eq!(Some("Note"),
+                   choice[0].reference.as_ref().map(|r| r.local_name.as_str()));
+        assert_eq!(("Sku", None), (choice[1].name.as_str(), choice[1].namespace.clone()));
+
+        assert_eq!(XsdAttributeUse::Required, order.attributes[0].attribute_use);
+        assert_eq!(None, order.attributes[0].namespace);
+        match schema.find_simple_type("Status").unwrap().variety {
+            XsdSimpleVariety::List { item_type: XsdTypeRef::Named(ref name) } => {
+                assert_eq!(Some(NS_XSD.to_string()), name.namespace)
+            }
+            ref other => panic!("Unexpected variety: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reads_types_of_examples_test() {
+        let wsdl = Wsdl::load_from_file(&example("travel_light/service.wsdl")).unwrap();
+        let schema = &wsdl.types[0].models[0];
+
+        let search = schema.find_complex_type("SearchFlights").unwrap();
+        match search.content {
+            XsdContent::Complex(ref derivation) => {
+                assert_eq!(XsdDerivationMethod::Extension, derivation.method);
+                assert_eq!("SoapMessageBase", derivation.base.local_name);
+                assert_eq!(Some("ElsyArres.API".to_string()), derivation.base.namespace);
+            }
+            ref other => panic!("Unexpected content: {:?}", other),
+        }
+        assert!(schema.find_complex_type("SoapMessageBase").unwrap().is_abstract);
+
+        let wsdl = Wsdl::load_from_file(&example("weather/etoimik.wsdl")).unwrap();
+        let schema = &wsdl.types[0].models[0];
+        assert_eq!(3, schema.imports.len());
+        assert!(!schema.elements.is_empty());
+        assert!(!schema.complex_types.is_empty());
+    }
+}